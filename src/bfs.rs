@@ -1,7 +1,12 @@
 use crate::graph::Graph;
 use rayon::prelude::*;
 use std::collections::VecDeque;
-use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::atomic::{AtomicI32, AtomicUsize, Ordering};
+
+// parent of the source and of unreachable nodes
+pub const NO_PARENT: usize = usize::MAX;
+// nearest source of unreachable nodes in multi-source BFS
+pub const NO_SOURCE: usize = usize::MAX;
 
 pub fn bfs_sequential(graph: &Graph, source: usize) -> Vec<i32> {
     let mut dist = vec![-1; graph.num_nodes];
@@ -11,7 +16,7 @@ pub fn bfs_sequential(graph: &Graph, source: usize) -> Vec<i32> {
     queue.push_back(source);
 
     while let Some(node) = queue.pop_front() {
-        for &neighbor in &graph.edges[node] {
+        for &neighbor in graph.neighbors(node) {
             if dist[neighbor] == -1 {
                 dist[neighbor] = dist[node] + 1;
                 queue.push_back(neighbor);
@@ -37,28 +42,295 @@ fn bfs_parallel_impl(graph: &Graph, source: usize) -> Vec<i32> {
     let mut current_level = vec![source];
     let mut level = 0;
 
+    while !current_level.is_empty() {
+        level += 1;
+        current_level = top_down_step(graph, &dist, &current_level, level);
+    }
+    dist.iter().map(|d| d.load(Ordering::Relaxed)).collect()
+}
+
+fn top_down_step(graph: &Graph, dist: &[AtomicI32], frontier: &[usize], level: i32) -> Vec<usize> {
+    frontier
+        .par_iter()
+        .flat_map_iter(|&node| {
+            graph.neighbors(node).iter().filter_map(move |&neighbor| {
+                if dist[neighbor]
+                    .compare_exchange(-1, level, Ordering::Relaxed, Ordering::Relaxed)
+                    .is_ok()
+                {
+                    Some(neighbor)
+                } else {
+                    None
+                }
+            })
+        })
+        .collect()
+}
+
+// every unvisited node looks for a parent among its in-neighbors, nodes of the
+// current frontier are exactly the ones with dist == level - 1
+fn bottom_up_step(graph: &Graph, dist: &[AtomicI32], level: i32) -> Vec<usize> {
+    (0..graph.num_nodes)
+        .into_par_iter()
+        .filter(|&node| {
+            if dist[node].load(Ordering::Relaxed) != -1 {
+                return false;
+            }
+            let found = graph
+                .in_neighbors(node)
+                .iter()
+                .any(|&parent| dist[parent].load(Ordering::Relaxed) == level - 1);
+            if found {
+                dist[node].store(level, Ordering::Relaxed);
+            }
+            found
+        })
+        .collect()
+}
+
+// direction-optimizing BFS (Beamer et al.), graph must have in-edges built (Graph::build_reverse)
+pub fn bfs_hybrid(graph: &Graph, source: usize, num_threads: usize) -> Vec<i32> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .unwrap()
+        .install(|| bfs_hybrid_impl(graph, source))
+}
+
+// switch thresholds from the paper
+const ALPHA: usize = 14;
+const BETA: usize = 24;
+
+fn bfs_hybrid_impl(graph: &Graph, source: usize) -> Vec<i32> {
+    assert!(graph.has_reverse(), "hybrid BFS needs in-edges");
+
+    let n = graph.num_nodes;
+    let dist: Vec<AtomicI32> = (0..n).map(|_| AtomicI32::new(-1)).collect();
+
+    dist[source].store(0, Ordering::Relaxed);
+    let mut frontier = vec![source];
+    let mut level = 0;
+
+    let mut bottom_up = false;
+    let mut frontier_edges = graph.out_degree(source); // m_f
+    let mut unexplored_edges = graph.num_edges(); // m_u
+
+    while !frontier.is_empty() {
+        level += 1;
+
+        if !bottom_up && frontier_edges > unexplored_edges / ALPHA {
+            bottom_up = true;
+        } else if bottom_up && frontier.len() < n / BETA {
+            bottom_up = false;
+        }
+        unexplored_edges = unexplored_edges.saturating_sub(frontier_edges);
+
+        frontier = if bottom_up {
+            bottom_up_step(graph, &dist, level)
+        } else {
+            top_down_step(graph, &dist, &frontier, level)
+        };
+
+        frontier_edges = frontier.par_iter().map(|&u| graph.out_degree(u)).sum();
+    }
+    dist.iter().map(|d| d.load(Ordering::Relaxed)).collect()
+}
+
+// BFS that also keeps the predecessor of every node, out of all parents on the
+// previous level the one with the smallest id is taken, so seq and par agree
+pub fn bfs_tree_sequential(graph: &Graph, source: usize) -> (Vec<i32>, Vec<usize>) {
+    let mut dist = vec![-1; graph.num_nodes];
+    let mut parent = vec![NO_PARENT; graph.num_nodes];
+    let mut queue = VecDeque::new();
+
+    dist[source] = 0;
+    queue.push_back(source);
+
+    while let Some(node) = queue.pop_front() {
+        for &neighbor in graph.neighbors(node) {
+            if dist[neighbor] == -1 {
+                dist[neighbor] = dist[node] + 1;
+                parent[neighbor] = node;
+                queue.push_back(neighbor);
+            } else if dist[neighbor] == dist[node] + 1 && node < parent[neighbor] {
+                parent[neighbor] = node;
+            }
+        }
+    }
+
+    (dist, parent)
+}
+
+pub fn bfs_tree_parallel(
+    graph: &Graph,
+    source: usize,
+    num_threads: usize,
+) -> (Vec<i32>, Vec<usize>) {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .unwrap()
+        .install(|| bfs_tree_parallel_impl(graph, source))
+}
+
+fn bfs_tree_parallel_impl(graph: &Graph, source: usize) -> (Vec<i32>, Vec<usize>) {
+    let dist: Vec<AtomicI32> = (0..graph.num_nodes).map(|_| AtomicI32::new(-1)).collect();
+    let parent: Vec<AtomicUsize> = (0..graph.num_nodes)
+        .map(|_| AtomicUsize::new(NO_PARENT))
+        .collect();
+
+    dist[source].store(0, Ordering::Relaxed);
+    let mut current_level = vec![source];
+    let mut level = 0;
+
     while !current_level.is_empty() {
         level += 1;
 
-        let next_level: Vec<usize> = current_level
+        current_level = current_level
             .par_iter()
             .flat_map_iter(|&node| {
-                graph.edges[node].iter().filter_map(|&neighbor| {
-                    if dist[neighbor]
-                        .compare_exchange(-1, level, Ordering::Relaxed, Ordering::Relaxed)
-                        .is_ok()
-                    {
-                        Some(neighbor)
-                    } else {
-                        None
+                let parent = &parent;
+                let dist = &dist;
+                graph.neighbors(node).iter().filter_map(move |&neighbor| {
+                    // whoever reaches the node on this level competes for the parent slot
+                    match dist[neighbor].compare_exchange(
+                        -1,
+                        level,
+                        Ordering::Relaxed,
+                        Ordering::Relaxed,
+                    ) {
+                        Ok(_) => {
+                            parent[neighbor].fetch_min(node, Ordering::Relaxed);
+                            Some(neighbor)
+                        }
+                        Err(d) if d == level => {
+                            parent[neighbor].fetch_min(node, Ordering::Relaxed);
+                            None
+                        }
+                        Err(_) => None,
                     }
                 })
             })
             .collect();
+    }
+
+    (
+        dist.iter().map(|d| d.load(Ordering::Relaxed)).collect(),
+        parent.iter().map(|p| p.load(Ordering::Relaxed)).collect(),
+    )
+}
+
+// BFS from all sources at once, every node gets the distance to its nearest source
+// and the source that claimed it (smallest id among equally near sources)
+pub fn bfs_multi_sequential(graph: &Graph, sources: &[usize]) -> (Vec<i32>, Vec<usize>) {
+    let mut dist = vec![-1; graph.num_nodes];
+    let mut claimed = vec![NO_SOURCE; graph.num_nodes];
+    let mut queue = VecDeque::new();
 
-        current_level = next_level;
+    for &source in sources {
+        if dist[source] == -1 {
+            dist[source] = 0;
+            claimed[source] = source;
+            queue.push_back(source);
+        }
     }
-    dist.iter().map(|d| d.load(Ordering::Relaxed)).collect()
+
+    while let Some(node) = queue.pop_front() {
+        for &neighbor in graph.neighbors(node) {
+            if dist[neighbor] == -1 {
+                dist[neighbor] = dist[node] + 1;
+                claimed[neighbor] = claimed[node];
+                queue.push_back(neighbor);
+            } else if dist[neighbor] == dist[node] + 1 && claimed[node] < claimed[neighbor] {
+                claimed[neighbor] = claimed[node];
+            }
+        }
+    }
+
+    (dist, claimed)
+}
+
+pub fn bfs_multi_parallel(
+    graph: &Graph,
+    sources: &[usize],
+    num_threads: usize,
+) -> (Vec<i32>, Vec<usize>) {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .unwrap()
+        .install(|| bfs_multi_parallel_impl(graph, sources))
+}
+
+fn bfs_multi_parallel_impl(graph: &Graph, sources: &[usize]) -> (Vec<i32>, Vec<usize>) {
+    let dist: Vec<AtomicI32> = (0..graph.num_nodes).map(|_| AtomicI32::new(-1)).collect();
+    let claimed: Vec<AtomicUsize> = (0..graph.num_nodes)
+        .map(|_| AtomicUsize::new(NO_SOURCE))
+        .collect();
+
+    let mut current_level: Vec<usize> = sources
+        .iter()
+        .copied()
+        .filter(|&source| dist[source].swap(0, Ordering::Relaxed) == -1)
+        .collect();
+    for &source in &current_level {
+        claimed[source].store(source, Ordering::Relaxed);
+    }
+    let mut level = 0;
+
+    while !current_level.is_empty() {
+        level += 1;
+
+        current_level = current_level
+            .par_iter()
+            .flat_map_iter(|&node| {
+                let dist = &dist;
+                let claimed = &claimed;
+                // sources of the previous level are final by now
+                let source = claimed[node].load(Ordering::Relaxed);
+                graph.neighbors(node).iter().filter_map(move |&neighbor| {
+                    match dist[neighbor].compare_exchange(
+                        -1,
+                        level,
+                        Ordering::Relaxed,
+                        Ordering::Relaxed,
+                    ) {
+                        Ok(_) => {
+                            claimed[neighbor].fetch_min(source, Ordering::Relaxed);
+                            Some(neighbor)
+                        }
+                        Err(d) if d == level => {
+                            claimed[neighbor].fetch_min(source, Ordering::Relaxed);
+                            None
+                        }
+                        Err(_) => None,
+                    }
+                })
+            })
+            .collect();
+    }
+
+    (
+        dist.iter().map(|d| d.load(Ordering::Relaxed)).collect(),
+        claimed.iter().map(|c| c.load(Ordering::Relaxed)).collect(),
+    )
+}
+
+// walks the parent array back from target, None if target was not reached from source
+pub fn reconstruct_path(parent: &[usize], source: usize, target: usize) -> Option<Vec<usize>> {
+    let mut path = vec![target];
+    let mut node = target;
+
+    while node != source {
+        node = parent[node];
+        if node == NO_PARENT {
+            return None;
+        }
+        path.push(node);
+    }
+
+    path.reverse();
+    Some(path)
 }
 
 #[cfg(test)]
@@ -68,15 +340,12 @@ mod tests {
 
     #[test]
     fn test_bfs_simple() {
-        let graph = Graph {
-            num_nodes: 4,
-            edges: vec![
-                vec![1, 3], // 0
-                vec![2],    // 1
-                vec![3],    // 2
-                vec![],     // 3
-            ],
-        };
+        let graph = Graph::from_adjacency(vec![
+            vec![1, 3], // 0
+            vec![2],    // 1
+            vec![3],    // 2
+            vec![],     // 3
+        ]);
 
         let result = bfs_sequential(&graph, 0);
 
@@ -87,16 +356,32 @@ mod tests {
     }
 
     #[test]
-    fn test_bfs_disconnected() {
-        let graph = Graph {
-            num_nodes: 4,
-            edges: vec![
-                vec![1], // 0
-                vec![],  // 1
-                vec![3], // 2
-                vec![],  // 3
-            ],
+    fn test_bfs_undirected_load() {
+        use crate::formats::{GraphFormat, LoadOptions, read_graph};
+
+        // 0→1, 1→2, 3→0: nothing is reachable from 2 along the edges
+        let path = "test_directed.txt";
+        let (graph, _) = read_graph(path, GraphFormat::EdgeList, &LoadOptions::default()).unwrap();
+        assert_eq!(bfs_sequential(&graph, 2)[0], -1);
+
+        let options = LoadOptions {
+            undirected: true,
+            ..LoadOptions::default()
         };
+        let (graph, _) = read_graph(path, GraphFormat::EdgeList, &options).unwrap();
+        assert_eq!(graph.num_edges(), 6);
+        assert_eq!(bfs_sequential(&graph, 2), vec![2, 1, 0, 3]);
+        assert_eq!(bfs_parallel(&graph, 2, 4), vec![2, 1, 0, 3]);
+    }
+
+    #[test]
+    fn test_bfs_disconnected() {
+        let graph = Graph::from_adjacency(vec![
+            vec![1], // 0
+            vec![],  // 1
+            vec![3], // 2
+            vec![],  // 3
+        ]);
 
         let result = bfs_sequential(&graph, 0);
 
@@ -108,10 +393,7 @@ mod tests {
 
     #[test]
     fn test_bfs_cycle() {
-        let graph = Graph {
-            num_nodes: 3,
-            edges: vec![vec![1], vec![2], vec![0]],
-        };
+        let graph = Graph::from_adjacency(vec![vec![1], vec![2], vec![0]]);
 
         let result = bfs_sequential(&graph, 0);
 
@@ -122,16 +404,13 @@ mod tests {
 
     #[test]
     fn test_bfs_parallel_vs_sequential() {
-        let graph = Graph {
-            num_nodes: 5,
-            edges: vec![
-                vec![1, 2], // 0→1, 0→2
-                vec![3],    // 1→3
-                vec![3],    // 2→3
-                vec![4],    // 3→4
-                vec![],     // 4
-            ],
-        };
+        let graph = Graph::from_adjacency(vec![
+            vec![1, 2], // 0→1, 0→2
+            vec![3],    // 1→3
+            vec![3],    // 2→3
+            vec![4],    // 3→4
+            vec![],     // 4
+        ]);
 
         let seq = bfs_sequential(&graph, 0);
         let par = bfs_parallel(&graph, 0, 4);
@@ -143,21 +422,18 @@ mod tests {
 
     #[test]
     fn test_bfs_parallel_large() {
-        let graph = Graph {
-            num_nodes: 10,
-            edges: vec![
-                vec![1, 2, 3],
-                vec![4],
-                vec![5],
-                vec![6],
-                vec![7],
-                vec![8],
-                vec![9],
-                vec![0],
-                vec![0],
-                vec![0],
-            ],
-        };
+        let graph = Graph::from_adjacency(vec![
+            vec![1, 2, 3],
+            vec![4],
+            vec![5],
+            vec![6],
+            vec![7],
+            vec![8],
+            vec![9],
+            vec![0],
+            vec![0],
+            vec![0],
+        ]);
 
         let seq = bfs_sequential(&graph, 0);
         let par = bfs_parallel(&graph, 0, 8);
@@ -167,17 +443,14 @@ mod tests {
 
     #[test]
     fn test_bfs_parallel_disconnected() {
-        let graph = Graph {
-            num_nodes: 6,
-            edges: vec![
-                vec![1], // 0→1
-                vec![2], // 1→2
-                vec![],  // 2
-                vec![4], // 3→4
-                vec![5], // 4→5
-                vec![],  // 5
-            ],
-        };
+        let graph = Graph::from_adjacency(vec![
+            vec![1], // 0→1
+            vec![2], // 1→2
+            vec![],  // 2
+            vec![4], // 3→4
+            vec![5], // 4→5
+            vec![],  // 5
+        ]);
 
         let seq = bfs_sequential(&graph, 0);
         let par = bfs_parallel(&graph, 0, 4);
@@ -188,4 +461,161 @@ mod tests {
         assert_eq!(par[4], -1);
         assert_eq!(par[5], -1);
     }
+
+    #[test]
+    fn test_bfs_hybrid_vs_sequential() {
+        let mut graph = Graph::from_adjacency(vec![
+            vec![1, 2, 3],
+            vec![4],
+            vec![5],
+            vec![6],
+            vec![7],
+            vec![8],
+            vec![9],
+            vec![0],
+            vec![0],
+            vec![0],
+        ]);
+        graph.build_reverse();
+
+        let seq = bfs_sequential(&graph, 0);
+        let hybrid = bfs_hybrid(&graph, 0, 4);
+
+        assert_eq!(seq, hybrid);
+    }
+
+    #[test]
+    fn test_bfs_hybrid_random() {
+        use crate::formats::GraphFormat;
+        use crate::graph_generator::generate_random_parallel;
+
+        let path = "test_bfs_hybrid.txt";
+        generate_random_parallel(2000, 20000, path, GraphFormat::EdgeList, false).unwrap();
+
+        let mut graph = Graph::from_file(path).unwrap();
+        graph.build_reverse();
+
+        for source in [0, 7, 1999] {
+            if source >= graph.num_nodes {
+                continue;
+            }
+            let seq = bfs_sequential(&graph, source);
+            let hybrid = bfs_hybrid(&graph, source, 4);
+            assert_eq!(seq, hybrid, "source {}", source);
+        }
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_bfs_hybrid_disconnected() {
+        let mut graph = Graph::from_adjacency(vec![
+            vec![1, 2],
+            vec![0, 2],
+            vec![],
+            vec![4],
+            vec![5],
+            vec![],
+        ]);
+        graph.build_reverse();
+
+        let seq = bfs_sequential(&graph, 0);
+        let hybrid = bfs_hybrid(&graph, 0, 4);
+
+        assert_eq!(seq, hybrid);
+        assert_eq!(hybrid[3], -1);
+    }
+
+    #[test]
+    fn test_bfs_tree_smallest_parent() {
+        // 3 is reachable through 1 and through 2, the smaller id wins
+        let graph = Graph::from_adjacency(vec![vec![2, 1], vec![3], vec![3], vec![]]);
+
+        let (dist, parent) = bfs_tree_sequential(&graph, 0);
+
+        assert_eq!(dist, vec![0, 1, 1, 2]);
+        assert_eq!(parent, vec![NO_PARENT, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_bfs_tree_parallel_vs_sequential() {
+        use crate::formats::GraphFormat;
+        use crate::graph_generator::generate_random_parallel;
+
+        let path = "test_bfs_tree.txt";
+        generate_random_parallel(2000, 10000, path, GraphFormat::EdgeList, false).unwrap();
+        let graph = Graph::from_file(path).unwrap();
+
+        let seq = bfs_tree_sequential(&graph, 0);
+        let par = bfs_tree_parallel(&graph, 0, 4);
+
+        assert_eq!(seq, par);
+        assert_eq!(seq.0, bfs_sequential(&graph, 0));
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_reconstruct_path() {
+        let graph = Graph::from_adjacency(vec![vec![1], vec![2], vec![3], vec![], vec![0]]);
+
+        let (_, parent) = bfs_tree_sequential(&graph, 0);
+
+        assert_eq!(reconstruct_path(&parent, 0, 3), Some(vec![0, 1, 2, 3]));
+        assert_eq!(reconstruct_path(&parent, 0, 0), Some(vec![0]));
+        assert_eq!(reconstruct_path(&parent, 0, 4), None);
+    }
+
+    #[test]
+    fn test_bfs_multi_simple() {
+        // line 0-1-2-3-4-5 in both directions, alarms at 0 and 5
+        let graph = Graph::from_adjacency(vec![
+            vec![1],
+            vec![0, 2],
+            vec![1, 3],
+            vec![2, 4],
+            vec![3, 5],
+            vec![4],
+        ]);
+
+        let (dist, claimed) = bfs_multi_sequential(&graph, &[5, 0]);
+
+        assert_eq!(dist, vec![0, 1, 2, 2, 1, 0]);
+        assert_eq!(claimed, vec![0, 0, 0, 5, 5, 5]);
+    }
+
+    #[test]
+    fn test_bfs_multi_tie_goes_to_smaller_source() {
+        // 1 and 3 are both one hop away from 2
+        let graph = Graph::from_adjacency(vec![vec![], vec![2], vec![], vec![2]]);
+
+        let seq = bfs_multi_sequential(&graph, &[3, 1]);
+        let par = bfs_multi_parallel(&graph, &[3, 1], 4);
+
+        assert_eq!(seq.1[2], 1);
+        assert_eq!(seq.1[0], NO_SOURCE);
+        assert_eq!(seq, par);
+    }
+
+    #[test]
+    fn test_bfs_multi_parallel_vs_sequential() {
+        use crate::formats::GraphFormat;
+        use crate::graph_generator::generate_random_parallel;
+
+        let path = "test_bfs_multi.txt";
+        generate_random_parallel(2000, 6000, path, GraphFormat::EdgeList, false).unwrap();
+        let graph = Graph::from_file(path).unwrap();
+        let sources = [5, 17, 300, 1200, 17];
+
+        let seq = bfs_multi_sequential(&graph, &sources);
+        let par = bfs_multi_parallel(&graph, &sources, 4);
+
+        assert_eq!(seq, par);
+
+        // single source is plain BFS
+        let (dist, _) = bfs_multi_sequential(&graph, &[0]);
+        assert_eq!(dist, bfs_sequential(&graph, 0));
+
+        std::fs::remove_file(path).ok();
+    }
 }
@@ -0,0 +1,389 @@
+use crate::graph::Graph;
+use crate::load_error::LoadError;
+use memmap2::Mmap;
+use rayon::prelude::*;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::ops::Deref;
+use std::sync::Arc;
+
+// binary CSR file, every field little-endian:
+//   magic "PDAJCSR\0", version u32, flags u32, num_nodes u64, num_edges u64,
+//   label bytes u64, checksum u64 (of everything after the header), header checksum u64
+//   (of the 48 bytes before it)
+//   offsets (num_nodes + 1) u64, targets num_edges u64,
+//   weights num_edges f64 (WEIGHTED), label offsets (num_nodes + 1) u64 + label bytes (LABELS)
+const MAGIC: &[u8; 8] = b"PDAJCSR\0";
+const VERSION: u32 = 2;
+const HEADER_LEN: usize = 56;
+
+const WEIGHTED: u32 = 1;
+const LABELS: u32 = 2;
+
+// sections are mapped straight into memory as usize/f64
+const NATIVE: bool = cfg!(all(target_endian = "little", target_pointer_width = "64"));
+
+// read-only array, either a Vec or a slice of a memory-mapped file
+pub struct Array<T: 'static> {
+    ptr: *const T,
+    len: usize,
+    _owner: Owner<T>,
+}
+
+// only there to keep the memory alive
+#[allow(dead_code)]
+enum Owner<T> {
+    Heap(Vec<T>),
+    Mapped(Arc<Mmap>),
+}
+
+// the data behind ptr is never written and the owner keeps it alive
+unsafe impl<T: Send + Sync> Send for Array<T> {}
+unsafe impl<T: Send + Sync> Sync for Array<T> {}
+
+impl<T> From<Vec<T>> for Array<T> {
+    fn from(vec: Vec<T>) -> Self {
+        Array {
+            ptr: vec.as_ptr(),
+            len: vec.len(),
+            _owner: Owner::Heap(vec),
+        }
+    }
+}
+
+impl<T> Deref for Array<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+// marker for the types a section can hold, every bit pattern is a valid value
+pub trait Plain: Copy {}
+impl Plain for usize {}
+impl Plain for f64 {}
+
+impl<T: Plain> Array<T> {
+    fn mapped(map: &Arc<Mmap>, start: usize, len: usize) -> Self {
+        let ptr = map[start..start + len * size_of::<T>()].as_ptr();
+        assert!(ptr.align_offset(align_of::<T>()) == 0);
+        Array {
+            ptr: ptr as *const T,
+            len,
+            _owner: Owner::Mapped(Arc::clone(map)),
+        }
+    }
+}
+
+fn as_bytes<T: Plain>(data: &[T]) -> &[u8] {
+    unsafe { std::slice::from_raw_parts(data.as_ptr() as *const u8, size_of_val(data)) }
+}
+
+// true if the file starts with the binary graph magic
+pub fn is_binary(path: &str) -> bool {
+    let mut magic = [0u8; 8];
+    File::open(path)
+        .and_then(|mut f| f.read_exact(&mut magic))
+        .is_ok_and(|_| &magic == MAGIC)
+}
+
+pub fn save_binary(graph: &Graph, path: &str) -> Result<(), Box<dyn Error>> {
+    if !NATIVE {
+        return Err("binary graph files need a 64-bit little-endian machine".into());
+    }
+
+    let (offsets, targets, weights) = graph.csr();
+    let (label_offsets, label_bytes) = match graph.labels() {
+        Some(labels) => {
+            let mut offsets = Vec::with_capacity(labels.len() + 1);
+            let mut bytes = Vec::new();
+            offsets.push(0);
+            for label in labels {
+                bytes.extend_from_slice(label.as_bytes());
+                offsets.push(bytes.len());
+            }
+            (Some(offsets), Some(bytes))
+        }
+        None => (None, None),
+    };
+
+    let mut sections = vec![as_bytes(offsets), as_bytes(targets)];
+    let mut flags = 0;
+    if let Some(weights) = weights {
+        flags |= WEIGHTED;
+        sections.push(as_bytes(weights));
+    }
+    if let (Some(offsets), Some(bytes)) = (&label_offsets, &label_bytes) {
+        flags |= LABELS;
+        sections.push(as_bytes(offsets));
+        sections.push(bytes);
+    }
+
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(MAGIC);
+    header.extend_from_slice(&VERSION.to_le_bytes());
+    header.extend_from_slice(&flags.to_le_bytes());
+    header.extend_from_slice(&(graph.num_nodes as u64).to_le_bytes());
+    header.extend_from_slice(&(targets.len() as u64).to_le_bytes());
+    header.extend_from_slice(&(label_bytes.as_ref().map_or(0, Vec::len) as u64).to_le_bytes());
+    header.extend_from_slice(&checksum(&sections).to_le_bytes());
+    let header_checksum = checksum(&[&header]);
+    header.extend_from_slice(&header_checksum.to_le_bytes());
+
+    let mut w = BufWriter::new(File::create(path)?);
+    w.write_all(&header)?;
+    for section in sections {
+        w.write_all(section)?;
+    }
+    w.flush()?;
+
+    Ok(())
+}
+
+// the file must not change while the graph is in use, it is mapped, not copied.
+// only the header and the offsets are checked so that big graphs open right away,
+// verify also checksums the whole file and checks every target (reads all of it)
+pub fn load_binary(path: &str, verify: bool) -> Result<Graph, LoadError> {
+    let error = |message: String| LoadError::format(path, None, message);
+    if !NATIVE {
+        return Err(error(
+            "binary graph files need a 64-bit little-endian machine".to_string(),
+        ));
+    }
+
+    let file = File::open(path).map_err(|e| LoadError::io(path, e))?;
+    let map = Arc::new(unsafe { Mmap::map(&file).map_err(|e| LoadError::io(path, e))? });
+
+    if map.len() < HEADER_LEN || &map[..8] != MAGIC {
+        return Err(error("not a binary graph file".to_string()));
+    }
+    let u32_at = |at: usize| u32::from_le_bytes(map[at..at + 4].try_into().unwrap());
+    let u64_at = |at: usize| u64::from_le_bytes(map[at..at + 8].try_into().unwrap()) as usize;
+
+    if checksum(&[&map[..HEADER_LEN - 8]]) != u64_at(HEADER_LEN - 8) as u64 {
+        // an older version has a different header, say that rather than "corrupt"
+        if u32_at(8) != VERSION {
+            return Err(error(format!(
+                "binary graph version {} is not supported (expected {})",
+                u32_at(8),
+                VERSION
+            )));
+        }
+        return Err(error("corrupt header (checksum mismatch)".to_string()));
+    }
+
+    let version = u32_at(8);
+    if version != VERSION {
+        return Err(error(format!(
+            "binary graph version {} is not supported (expected {})",
+            version, VERSION
+        )));
+    }
+    let flags = u32_at(12);
+    let num_nodes = u64_at(16);
+    let num_edges = u64_at(24);
+    let label_len = u64_at(32);
+    let expected_checksum = u64_at(40) as u64;
+
+    // (start, length in bytes) of every section
+    let mut layout = Vec::new();
+    let mut at = HEADER_LEN;
+    // saturating so that a garbage header ends in the size check below
+    let mut section = |bytes: usize| {
+        let start = at;
+        layout.push((start, bytes));
+        at = at.saturating_add(bytes);
+        (start, bytes)
+    };
+    let node_array = num_nodes.saturating_add(1).saturating_mul(8);
+    let edge_array = num_edges.saturating_mul(8);
+    let offsets_at = section(node_array);
+    let targets_at = section(edge_array);
+    let weights_at = (flags & WEIGHTED != 0).then(|| section(edge_array));
+    let labels_at = (flags & LABELS != 0).then(|| (section(node_array), section(label_len)));
+
+    if at != map.len() {
+        return Err(error(format!(
+            "file is {} bytes but its header describes {} bytes (truncated file?)",
+            map.len(),
+            at
+        )));
+    }
+
+    if verify {
+        let sections: Vec<&[u8]> = layout.iter().map(|&(s, len)| &map[s..s + len]).collect();
+        if checksum(&sections) != expected_checksum {
+            return Err(error("corrupt file (checksum mismatch)".to_string()));
+        }
+    }
+
+    // neighbors() slices targets with these and algorithms index with the targets,
+    // so both have to be sane even without verify
+    let offsets: Array<usize> = Array::mapped(&map, offsets_at.0, num_nodes + 1);
+    if offsets[0] != 0
+        || offsets[num_nodes] != num_edges
+        || offsets.par_windows(2).any(|w| w[0] > w[1])
+    {
+        return Err(error("inconsistent offsets".to_string()));
+    }
+    let targets: Array<usize> = Array::mapped(&map, targets_at.0, num_edges);
+    if targets.par_iter().any(|&v| v >= num_nodes) {
+        return Err(error("edge target out of range".to_string()));
+    }
+    let weights = weights_at.map(|(start, _)| Array::mapped(&map, start, num_edges));
+
+    let labels = match labels_at {
+        Some(((offsets_start, _), (bytes_start, bytes_len))) => {
+            let label_offsets: Array<usize> = Array::mapped(&map, offsets_start, num_nodes + 1);
+            let bytes = &map[bytes_start..bytes_start + bytes_len];
+            let labels = label_offsets
+                .windows(2)
+                .map(|w| {
+                    bytes
+                        .get(w[0]..w[1])
+                        .and_then(|b| std::str::from_utf8(b).ok())
+                        .map(str::to_string)
+                })
+                .collect::<Option<Vec<String>>>()
+                .ok_or_else(|| error("broken node labels".to_string()))?;
+            Some(labels)
+        }
+        None => None,
+    };
+
+    Ok(Graph::from_csr(
+        num_nodes, offsets, targets, weights, labels,
+    ))
+}
+
+// FNV-1a over 8-byte words, 1 MiB blocks are hashed in parallel and then combined in order
+fn checksum(sections: &[&[u8]]) -> u64 {
+    const BLOCK: usize = 1 << 20;
+    const PRIME: u64 = 0x100000001b3;
+
+    let block_hash = |block: &[u8]| {
+        let mut h: u64 = 0xcbf29ce484222325;
+        for word in block.chunks(8) {
+            let mut buf = [0u8; 8];
+            buf[..word.len()].copy_from_slice(word);
+            h = (h ^ u64::from_le_bytes(buf)).wrapping_mul(PRIME);
+        }
+        h
+    };
+
+    sections.iter().fold(0u64, |acc, section| {
+        let blocks: Vec<u64> = section.par_chunks(BLOCK).map(block_hash).collect();
+        let h = blocks
+            .iter()
+            .fold(section.len() as u64, |h, &b| (h ^ b).wrapping_mul(PRIME));
+        (acc ^ h).wrapping_mul(PRIME).rotate_left(17)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binary_roundtrip() {
+        let path = "test_binary_roundtrip.bin";
+        let graph = Graph::from_weighted_edges(4, &[(0, 1, 1.5), (2, 3, 0.5), (0, 2, 2.0)]);
+        save_binary(&graph, path).unwrap();
+
+        assert!(is_binary(path));
+        let loaded = load_binary(path, false).unwrap();
+
+        assert_eq!(loaded.num_nodes, 4);
+        assert_eq!(loaded.num_edges(), 3);
+        for u in 0..4 {
+            assert_eq!(
+                loaded.weighted_neighbors(u).collect::<Vec<_>>(),
+                graph.weighted_neighbors(u).collect::<Vec<_>>()
+            );
+        }
+        assert!(loaded.labels().is_none());
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_binary_labels() {
+        let input = "test_binary_labels.txt";
+        let path = "test_binary_labels.bin";
+        std::fs::write(input, "web-01 db\ndb cache\n").unwrap();
+        let graph = Graph::from_file_relabeled(input).unwrap();
+        save_binary(&graph, path).unwrap();
+
+        let mut loaded = load_binary(path, false).unwrap();
+        assert_eq!(loaded.labels(), graph.labels());
+        assert!(!loaded.is_weighted());
+
+        // mapped arrays are replaced, not written to
+        loaded.symmetrize();
+        assert_eq!(loaded.neighbors(1), &[2, 0]);
+
+        std::fs::remove_file(input).ok();
+        std::fs::remove_file(path).ok();
+    }
+
+    fn load_error(path: &str, verify: bool) -> String {
+        match load_binary(path, verify) {
+            Ok(_) => panic!("{} should not load", path),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_binary_rejects_bad_files() {
+        let path = "test_binary_bad.bin";
+        let graph = Graph::from_edges(3, &[(0, 1), (1, 2)]);
+        save_binary(&graph, path).unwrap();
+        let good = std::fs::read(path).unwrap();
+
+        // target 2 becomes huge, caught without verify
+        let mut bytes = good.clone();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        std::fs::write(path, &bytes).unwrap();
+        assert!(load_error(path, false).contains("out of range"));
+        assert!(load_error(path, true).contains("checksum"));
+
+        // target 1 becomes 0, still a node, only the checksum notices
+        let mut bytes = good.clone();
+        bytes[HEADER_LEN + 4 * 8] ^= 1;
+        std::fs::write(path, &bytes).unwrap();
+        assert!(load_binary(path, false).is_ok());
+        assert!(load_error(path, true).contains("checksum"));
+
+        // truncated
+        std::fs::write(path, &good[..good.len() - 8]).unwrap();
+        assert!(load_error(path, false).contains("truncated"));
+
+        // newer version
+        let mut bytes = good.clone();
+        bytes[8] = 9;
+        std::fs::write(path, &bytes).unwrap();
+        assert!(load_error(path, false).contains("version"));
+
+        // any other header change
+        let mut bytes = good.clone();
+        bytes[16] ^= 1;
+        std::fs::write(path, &bytes).unwrap();
+        assert!(load_error(path, false).contains("corrupt header"));
+
+        // offsets 0, 2, 1, 2 go backwards, caught without verify
+        let mut bytes = good.clone();
+        bytes[HEADER_LEN + 8] = 2;
+        bytes[HEADER_LEN + 16] = 1;
+        std::fs::write(path, &bytes).unwrap();
+        assert!(load_error(path, false).contains("offsets"));
+
+        std::fs::write(path, "0 1\n").unwrap();
+        assert!(!is_binary(path));
+        assert!(load_binary(path, true).is_err());
+
+        std::fs::remove_file(path).ok();
+    }
+}
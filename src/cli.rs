@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 
 #[derive(Parser)]
 #[command(name = "pdaj-projekat")]
@@ -8,28 +8,207 @@ pub struct Cli {
     pub command: Commands,
 }
 
+// how the input graph is read, shared by every algorithm command
+#[derive(Args)]
+pub struct LoadArgs {
+    //edge list, or a binary graph file written by the convert command
+    #[arg(long)]
+    pub input: String,
+
+    //edgelist, snap, mtx, dimacs, metis, graphml or binary, guessed from the file if not set
+    #[arg(long)]
+    pub input_format: Option<String>,
+
+    //node ids are arbitrary tokens (strings, sparse 64-bit ids), results use the original ids
+    #[arg(long)]
+    pub relabel: bool,
+
+    //skip malformed lines and print a summary of them, by default a bad line fails the load
+    #[arg(long)]
+    pub lenient: bool,
+
+    //ignore edge direction: every u→v also gets v→u (reverse edges already in the file are not doubled)
+    #[arg(long)]
+    pub undirected: bool,
+
+    //binary graph files: checksum the whole file before using it (reads all of it)
+    #[arg(long)]
+    pub verify: bool,
+
+    #[command(flatten)]
+    pub clean: CleanArgs,
+}
+
+// cleaning right after loading (see Graph::clean), the clean command does all of it when nothing is set
+#[derive(Args)]
+pub struct CleanArgs {
+    //sort every adjacency list by target
+    #[arg(long)]
+    pub sort: bool,
+
+    //remove repeated edges, the first one (and its weight) is kept
+    #[arg(long)]
+    pub dedup: bool,
+
+    #[arg(long)]
+    pub drop_self_loops: bool,
+
+    //remove nodes without edges, results keep the ids of the input file
+    #[arg(long)]
+    pub drop_isolated: bool,
+
+    //all of the above
+    #[arg(long)]
+    pub clean: bool,
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
     Bfs {
+        #[command(flatten)]
+        load: LoadArgs,
+
+        #[arg(long)]
+        source: Option<String>,
+
+        //multi-source BFS, "3,7,9"
+        #[arg(long)]
+        sources: Option<String>,
+
+        //file with one source node per line
+        #[arg(long)]
+        sources_file: Option<String>,
+
+        #[arg(long)]
+        mode: String,
+
+        #[arg(long)]
+        threads: Option<usize>,
+
+        //also write the BFS tree parent of every node (seq and par modes)
+        #[arg(long)]
+        parents: bool,
+
+        //forward follows out-edges, backward in-edges (upstream), both ignores direction
+        #[arg(long, default_value = "forward")]
+        direction: String,
+
+        #[arg(long)]
+        out: String,
+
+        // text, csv, json or jsonl
+        #[arg(long, default_value = "text")]
+        format: String,
+
+        // gzip or zstd, guessed from .gz/.zst extension of --out if not set
+        #[arg(long)]
+        compress: Option<String>,
+    },
+
+    Path {
+        #[command(flatten)]
+        load: LoadArgs,
+
         #[arg(long)]
-        input: String,
+        source: String,
 
         #[arg(long)]
-        source: usize,
+        target: String,
 
         #[arg(long)]
         mode: String,
 
         #[arg(long)]
         threads: Option<usize>,
+    },
+
+    Sssp {
+        #[command(flatten)]
+        load: LoadArgs,
+
+        #[arg(long)]
+        source: String,
+
+        #[arg(long)]
+        mode: String,
+
+        #[arg(long)]
+        threads: Option<usize>,
+
+        //bucket width for delta-stepping, mean edge weight by default
+        #[arg(long)]
+        delta: Option<f64>,
 
         #[arg(long)]
         out: String,
+
+        // text, csv, json or jsonl
+        #[arg(long, default_value = "text")]
+        format: String,
+
+        // gzip or zstd, guessed from .gz/.zst extension of --out if not set
+        #[arg(long)]
+        compress: Option<String>,
     },
 
     Wcc {
+        #[command(flatten)]
+        load: LoadArgs,
+
         #[arg(long)]
-        input: String,
+        mode: String,
+
+        #[arg(long)]
+        threads: Option<usize>,
+
+        #[arg(long)]
+        out: String,
+
+        // text, csv, json or jsonl
+        #[arg(long, default_value = "text")]
+        format: String,
+
+        // gzip or zstd, guessed from .gz/.zst extension of --out if not set
+        #[arg(long)]
+        compress: Option<String>,
+
+        //par mode: union-find, afforest, sv (Shiloach-Vishkin) or lp (label propagation)
+        #[arg(long, default_value = "union-find")]
+        algorithm: String,
+
+        //component ids: min (smallest member), dense (0..k, biggest first) or root (raw union-find roots)
+        #[arg(long, default_value = "min")]
+        labels: String,
+
+        //how many of the biggest components to list
+        #[arg(long, default_value = "10")]
+        top: usize,
+
+        //"rank node" lines with the members of the --top biggest components
+        #[arg(long)]
+        members: Option<String>,
+    },
+
+    //keeps the components of --input up to date while edge batches arrive, answers queries in between
+    Incremental {
+        #[command(flatten)]
+        load: LoadArgs,
+
+        #[arg(long)]
+        threads: Option<usize>,
+
+        //"u v" edge lines and "connected u v", "size u", "components" queries, stdin if not set
+        #[arg(long)]
+        updates: Option<String>,
+
+        //pending edges are applied after this many even without a query
+        #[arg(long, default_value = "100000")]
+        batch_size: usize,
+    },
+
+    Scc {
+        #[command(flatten)]
+        load: LoadArgs,
 
         #[arg(long)]
         mode: String,
@@ -39,11 +218,51 @@ pub enum Commands {
 
         #[arg(long)]
         out: String,
+
+        // text, csv, json or jsonl
+        #[arg(long, default_value = "text")]
+        format: String,
+
+        // gzip or zstd, guessed from .gz/.zst extension of --out if not set
+        #[arg(long)]
+        compress: Option<String>,
     },
 
-    Pagerank {
+    //articulation points, bridges and biconnected components, edge direction is ignored
+    Critical {
+        #[command(flatten)]
+        load: LoadArgs,
+
         #[arg(long)]
-        input: String,
+        mode: String,
+
+        #[arg(long)]
+        threads: Option<usize>,
+
+        //per node: how many nodes it cuts off when it fails (0 if it is not an articulation point)
+        #[arg(long)]
+        out: String,
+
+        // text, csv, json or jsonl
+        #[arg(long, default_value = "text")]
+        format: String,
+
+        // gzip or zstd, guessed from .gz/.zst extension of --out if not set
+        #[arg(long)]
+        compress: Option<String>,
+
+        //edge list of the bridges
+        #[arg(long)]
+        bridges: Option<String>,
+
+        //"block node" lines, one for every node of every biconnected component
+        #[arg(long)]
+        blocks: Option<String>,
+    },
+
+    Pagerank {
+        #[command(flatten)]
+        load: LoadArgs,
 
         #[arg(long)]
         mode: String,
@@ -54,6 +273,14 @@ pub enum Commands {
         #[arg(long)]
         out: String,
 
+        // text, csv, json or jsonl
+        #[arg(long, default_value = "text")]
+        format: String,
+
+        // gzip or zstd, guessed from .gz/.zst extension of --out if not set
+        #[arg(long)]
+        compress: Option<String>,
+
         #[arg(long, default_value = "0.85")]
         alpha: f64,
 
@@ -62,6 +289,90 @@ pub enum Commands {
 
         #[arg(long, default_value = "1e-10")]
         eps: f64,
+
+        // uniform, personalization, self-loop or ignore
+        #[arg(long, default_value = "uniform")]
+        dangling: String,
+
+        //personalized PageRank, seed nodes "3,7:2.5" (node:weight)
+        #[arg(long)]
+        seeds: Option<String>,
+
+        //file with one seed node per line, optionally followed by weight
+        #[arg(long)]
+        seeds_file: Option<String>,
+    },
+
+    //removes nodes in the chosen order and follows the largest weakly connected component
+    Resilience {
+        #[command(flatten)]
+        load: LoadArgs,
+
+        #[arg(long)]
+        threads: Option<usize>,
+
+        //random, degree, pagerank or betweenness, scores are computed once on the whole graph
+        #[arg(long, default_value = "degree")]
+        order: String,
+
+        //number of measuring points between no node and every node removed
+        #[arg(long, default_value = "20")]
+        steps: usize,
+
+        //betweenness: BFS sources, more is slower and closer to the exact value
+        #[arg(long, default_value = "64")]
+        samples: usize,
+
+        //random order and betweenness sources: the same seed gives the same result
+        #[arg(long)]
+        seed: Option<u64>,
+
+        //"removed fraction largest components" lines
+        #[arg(long)]
+        out: Option<String>,
+    },
+
+    //node/edge counts, degrees, self-loops, duplicates, reciprocity
+    Stats {
+        #[command(flatten)]
+        load: LoadArgs,
+
+        #[arg(long)]
+        threads: Option<usize>,
+
+        //also write the statistics to this file
+        #[arg(long)]
+        out: Option<String>,
+
+        // text or json, for --out
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+
+    //writes the graph in another format, binary CSR (loaded much faster by every other command) by default
+    Convert {
+        #[command(flatten)]
+        load: LoadArgs,
+
+        #[arg(long)]
+        output: String,
+
+        //edgelist, snap, mtx, dimacs, metis, graphml or binary, guessed from --output extension if not set
+        #[arg(long)]
+        format: Option<String>,
+    },
+
+    //removes self-loops, duplicate edges and isolated nodes, sorts adjacency lists and writes the graph
+    Clean {
+        #[command(flatten)]
+        load: LoadArgs,
+
+        #[arg(long)]
+        output: String,
+
+        //edgelist, snap, mtx, dimacs, metis, graphml or binary, guessed from --output extension if not set
+        #[arg(long)]
+        format: Option<String>,
     },
 
     Generate {
@@ -81,5 +392,13 @@ pub enum Commands {
 
         #[arg(long)]
         output: String,
+
+        //edgelist, snap, mtx, dimacs, metis, graphml or binary, guessed from --output extension if not set
+        #[arg(long)]
+        format: Option<String>,
+
+        //write every edge in both directions
+        #[arg(long)]
+        undirected: bool,
     },
 }
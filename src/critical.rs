@@ -0,0 +1,326 @@
+use crate::graph::Graph;
+use crate::wcc::{wcc_parallel, wcc_sequential};
+use rayon::prelude::*;
+
+// single points of failure of an undirected graph
+pub struct Critical {
+    // nodes that lose the connection to the biggest remaining part of their
+    // component when this node fails, 0 for nodes that are not articulation points
+    pub cut_off: Vec<usize>,
+    // (u, v) with u < v, sorted
+    pub bridges: Vec<(usize, usize)>,
+    // node sets of the biconnected components (a bridge is a 2-node block), sorted,
+    // isolated nodes are in none of them
+    pub blocks: Vec<Vec<usize>>,
+}
+
+impl Critical {
+    pub fn articulation_points(&self) -> Vec<usize> {
+        (0..self.cut_off.len())
+            .filter(|&u| self.cut_off[u] > 0)
+            .collect()
+    }
+}
+
+// what one component contributes
+struct Part {
+    cut_off: Vec<(usize, usize)>,
+    bridges: Vec<(usize, usize)>,
+    blocks: Vec<Vec<usize>>,
+}
+
+// graph must be symmetric without self-loops and duplicate edges (see Graph::symmetrize, Graph::clean)
+pub fn critical_sequential(graph: &Graph) -> Critical {
+    let (components, local) = components(&wcc_sequential(graph));
+    let parts: Vec<Part> = components
+        .iter()
+        .map(|nodes| biconnected(graph, nodes, &local))
+        .collect();
+    merge(graph.num_nodes, parts)
+}
+
+// the DFS itself can't be split, every component gets its own DFS on the pool
+// (biggest first), so this helps graphs with many components, not one giant one
+pub fn critical_parallel(graph: &Graph, num_threads: usize) -> Critical {
+    let labels = wcc_parallel(graph, num_threads);
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .unwrap()
+        .install(|| {
+            let (mut components, local) = components(&labels);
+            components.par_sort_by_key(|nodes| std::cmp::Reverse(nodes.len()));
+            let parts: Vec<Part> = components
+                .par_iter()
+                .with_max_len(1)
+                .map(|nodes| biconnected(graph, nodes, &local))
+                .collect();
+            merge(graph.num_nodes, parts)
+        })
+}
+
+// nodes of every component with more than one node in ascending order,
+// and the index of every node inside its component
+fn components(labels: &[usize]) -> (Vec<Vec<usize>>, Vec<usize>) {
+    let mut index = vec![usize::MAX; labels.len()];
+    let mut components: Vec<Vec<usize>> = Vec::new();
+    let mut local = vec![0; labels.len()];
+
+    for (u, &label) in labels.iter().enumerate() {
+        if index[label] == usize::MAX {
+            index[label] = components.len();
+            components.push(Vec::new());
+        }
+        let nodes = &mut components[index[label]];
+        local[u] = nodes.len();
+        nodes.push(u);
+    }
+
+    components.retain(|nodes| nodes.len() > 1);
+    (components, local)
+}
+
+const NONE: usize = usize::MAX;
+
+// iterative Hopcroft-Tarjan DFS from nodes[0], arrays are indexed by the position in the component
+fn biconnected(graph: &Graph, nodes: &[usize], local: &[usize]) -> Part {
+    let k = nodes.len();
+    let mut disc = vec![NONE; k];
+    let mut low = vec![0; k];
+    let mut size = vec![1; k];
+    let mut parent = vec![NONE; k];
+    let mut next = vec![0; k];
+    // sizes of the subtrees that only hang on the node: their sum, the biggest one and how many
+    let mut split = vec![(0, 0, 0); k];
+
+    let mut part = Part {
+        cut_off: Vec::new(),
+        bridges: Vec::new(),
+        blocks: Vec::new(),
+    };
+
+    let mut calls = vec![0];
+    let mut visited = vec![0];
+    disc[0] = 0;
+    low[0] = 0;
+    let mut time = 1;
+
+    while let Some(&v) = calls.last() {
+        let neighbors = graph.neighbors(nodes[v]);
+        if next[v] < neighbors.len() {
+            let w = local[neighbors[next[v]]];
+            next[v] += 1;
+            if disc[w] == NONE {
+                parent[w] = v;
+                disc[w] = time;
+                low[w] = time;
+                time += 1;
+                calls.push(w);
+                visited.push(w);
+            } else if w != parent[v] {
+                low[v] = low[v].min(disc[w]);
+            }
+            continue;
+        }
+
+        calls.pop();
+        let p = parent[v];
+        if p == NONE {
+            continue;
+        }
+        low[p] = low[p].min(low[v]);
+        size[p] += size[v];
+
+        if low[v] > disc[p] {
+            let (a, b) = (nodes[p], nodes[v]);
+            part.bridges.push((a.min(b), a.max(b)));
+        }
+        if low[v] >= disc[p] {
+            let (sum, max, count) = split[p];
+            split[p] = (sum + size[v], max.max(size[v]), count + 1);
+
+            // v's subtree still on the stack, plus p, is one block
+            let mut block = vec![nodes[p]];
+            while let Some(w) = visited.pop() {
+                block.push(nodes[w]);
+                if w == v {
+                    break;
+                }
+            }
+            block.sort_unstable();
+            part.blocks.push(block);
+        }
+    }
+
+    for v in 0..k {
+        let (sum, max, count) = split[v];
+        // the root has no part above it and needs two subtrees, other nodes one
+        let rest = if v == 0 { 0 } else { k - 1 - sum };
+        let pieces = count + usize::from(rest > 0);
+        if pieces > 1 {
+            part.cut_off.push((nodes[v], sum + rest - max.max(rest)));
+        }
+    }
+
+    part
+}
+
+fn merge(num_nodes: usize, parts: Vec<Part>) -> Critical {
+    let mut critical = Critical {
+        cut_off: vec![0; num_nodes],
+        bridges: Vec::new(),
+        blocks: Vec::new(),
+    };
+
+    for part in parts {
+        for (u, count) in part.cut_off {
+            critical.cut_off[u] = count;
+        }
+        critical.bridges.extend(part.bridges);
+        critical.blocks.extend(part.blocks);
+    }
+
+    critical.bridges.par_sort_unstable();
+    critical.blocks.par_sort_unstable();
+    critical
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::CleanOptions;
+
+    fn undirected(num_nodes: usize, edges: &[(usize, usize)]) -> Graph {
+        let mut graph = Graph::from_edges(num_nodes, edges);
+        graph.symmetrize();
+        graph.clean(&CleanOptions {
+            dedup: true,
+            self_loops: true,
+            ..CleanOptions::default()
+        });
+        graph
+    }
+
+    // removes every node in turn and counts what is no longer reachable from the biggest part
+    fn brute_force_cut_off(graph: &Graph) -> Vec<usize> {
+        let n = graph.num_nodes;
+        let labels = wcc_sequential(graph);
+        (0..n)
+            .map(|removed| {
+                let component: Vec<usize> = (0..n)
+                    .filter(|&u| u != removed && labels[u] == labels[removed])
+                    .collect();
+                let mut seen = vec![false; n];
+                let mut sizes = Vec::new();
+                for &start in &component {
+                    if seen[start] {
+                        continue;
+                    }
+                    seen[start] = true;
+                    let mut stack = vec![start];
+                    let mut size = 0;
+                    while let Some(u) = stack.pop() {
+                        size += 1;
+                        for &v in graph.neighbors(u) {
+                            if v != removed && !seen[v] {
+                                seen[v] = true;
+                                stack.push(v);
+                            }
+                        }
+                    }
+                    sizes.push(size);
+                }
+                component.len() - sizes.iter().max().unwrap_or(&0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_critical_small() {
+        // triangle 0-1-2, bridge 2-3, triangle 3-4-5, tail 5-6, 7-8 apart, 9 alone
+        let graph = undirected(
+            10,
+            &[
+                (0, 1),
+                (1, 2),
+                (2, 0),
+                (2, 3),
+                (3, 4),
+                (4, 5),
+                (5, 3),
+                (6, 5),
+                (7, 8),
+                (8, 7),
+            ],
+        );
+        let critical = critical_sequential(&graph);
+
+        assert_eq!(critical.articulation_points(), vec![2, 3, 5]);
+        // 2 cuts off its triangle (2 nodes), 3 the triangle 0-1-2, 5 only 6
+        assert_eq!(critical.cut_off[2], 2);
+        assert_eq!(critical.cut_off[3], 3);
+        assert_eq!(critical.cut_off[5], 1);
+        assert_eq!(critical.bridges, vec![(2, 3), (5, 6), (7, 8)]);
+        assert_eq!(
+            critical.blocks,
+            vec![
+                vec![0, 1, 2],
+                vec![2, 3],
+                vec![3, 4, 5],
+                vec![5, 6],
+                vec![7, 8]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_critical_star_root() {
+        // the DFS starts in the center
+        let graph = undirected(4, &[(0, 1), (0, 2), (0, 3)]);
+        let critical = critical_sequential(&graph);
+
+        assert_eq!(critical.articulation_points(), vec![0]);
+        assert_eq!(critical.cut_off[0], 2);
+        assert_eq!(critical.bridges.len(), 3);
+    }
+
+    #[test]
+    fn test_critical_vs_brute_force() {
+        let mut edges = Vec::new();
+        let mut x: usize = 7;
+        for _ in 0..260 {
+            x = x
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let u = (x >> 33) % 200;
+            x = x
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let v = (x >> 33) % 200;
+            edges.push((u, v));
+        }
+        let graph = undirected(200, &edges);
+
+        let seq = critical_sequential(&graph);
+        assert_eq!(seq.cut_off, brute_force_cut_off(&graph));
+
+        let par = critical_parallel(&graph, 4);
+        assert_eq!(par.cut_off, seq.cut_off);
+        assert_eq!(par.bridges, seq.bridges);
+        assert_eq!(par.blocks, seq.blocks);
+    }
+
+    #[test]
+    fn test_critical_deep_path() {
+        // a recursive DFS would overflow the stack here
+        let n = 200_000;
+        let edges: Vec<(usize, usize)> = (0..n - 1).map(|i| (i, i + 1)).collect();
+        let graph = undirected(n, &edges);
+        let critical = critical_sequential(&graph);
+
+        assert_eq!(critical.bridges.len(), n - 1);
+        assert_eq!(critical.cut_off[1], 1);
+        assert_eq!(critical.cut_off[n / 2], n / 2 - 1);
+        assert_eq!(critical.cut_off[0], 0);
+    }
+}
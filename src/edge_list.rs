@@ -0,0 +1,252 @@
+use crate::load_error::{LoadError, Problem, Problems};
+use rayon::prelude::*;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+// "src dst [weight]" lines, weights is None when no line had a weight
+pub struct EdgeList {
+    pub edges: Vec<(usize, usize)>,
+    pub weights: Option<Vec<f64>>,
+}
+
+// line by line, node_id turns a token into a node index (needed when the
+// index depends on the order in which ids appear, e.g. relabeling)
+// bad lines are skipped and returned, the caller decides if that is an error
+pub fn read_edge_list<F>(path: &str, mut node_id: F) -> Result<(EdgeList, Problems), LoadError>
+where
+    F: FnMut(&str) -> usize,
+{
+    let file = File::open(path).map_err(|e| LoadError::io(path, e))?;
+    let reader = BufReader::new(file);
+    let mut edges_temp: Vec<(usize, usize)> = Vec::new();
+    let mut weights_temp: Vec<f64> = Vec::new();
+    let mut weighted = false;
+    let mut problems = Problems::default();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| LoadError::io(path, e))?;
+        match parse_line(&line) {
+            Ok(Some((src, dst, weight))) => {
+                weighted |= weight.is_some();
+                edges_temp.push((node_id(src), node_id(dst)));
+                weights_temp.push(weight.unwrap_or(1.0));
+            }
+            Ok(None) => {}
+            Err(problem) => problems.push(i + 1, problem, &line),
+        }
+    }
+
+    let list = EdgeList {
+        edges: edges_temp,
+        weights: weighted.then_some(weights_temp),
+    };
+    Ok((list, problems))
+}
+
+// numeric ids only: the file is split into chunks at line boundaries and the
+// chunks are parsed in parallel, edges keep the order they have in the file
+pub fn parse_edge_list(path: &str) -> Result<(EdgeList, usize, Problems), LoadError> {
+    let text = std::fs::read_to_string(path).map_err(|e| LoadError::io(path, e))?;
+    let chunks = split_lines(&text, rayon::current_num_threads() * 4);
+
+    let parsed: Vec<Chunk> = chunks.par_iter().map(|c| parse_chunk(c)).collect();
+
+    let mut edges = Vec::new();
+    let mut weights = Vec::new();
+    let mut weighted = false;
+    let mut max_node = 0;
+    let mut problems = Problems::default();
+    // line numbers of a chunk start after the lines of the chunks before it
+    let mut lines = 0;
+    for chunk in parsed {
+        weighted |= chunk.weighted;
+        max_node = max_node.max(chunk.max_node);
+        problems.append(chunk.problems, lines);
+        lines += chunk.lines;
+        if edges.is_empty() {
+            edges = chunk.edges;
+            weights = chunk.weights;
+        } else {
+            edges.extend(chunk.edges);
+            weights.extend(chunk.weights);
+        }
+    }
+
+    let list = EdgeList {
+        edges,
+        weights: weighted.then_some(weights),
+    };
+    Ok((list, max_node, problems))
+}
+
+struct Chunk {
+    edges: Vec<(usize, usize)>,
+    weights: Vec<f64>,
+    weighted: bool,
+    max_node: usize,
+    lines: usize,
+    // line numbers counted from the start of the chunk
+    problems: Problems,
+}
+
+fn parse_chunk(text: &str) -> Chunk {
+    let mut chunk = Chunk {
+        edges: Vec::new(),
+        weights: Vec::new(),
+        weighted: false,
+        max_node: 0,
+        lines: 0,
+        problems: Problems::default(),
+    };
+
+    for line in text.lines() {
+        chunk.lines += 1;
+        let parsed = parse_line(line).and_then(|parsed| match parsed {
+            Some((src, dst, weight)) => Ok(Some((parse_id(src)?, parse_id(dst)?, weight))),
+            None => Ok(None),
+        });
+
+        match parsed {
+            Ok(Some((src, dst, weight))) => {
+                chunk.max_node = chunk.max_node.max(src).max(dst);
+                chunk.weighted |= weight.is_some();
+                chunk.edges.push((src, dst));
+                chunk.weights.push(weight.unwrap_or(1.0));
+            }
+            Ok(None) => {}
+            Err(problem) => chunk.problems.push(chunk.lines, problem, line),
+        }
+    }
+
+    chunk
+}
+
+// string ids need --relabel
+fn parse_id(token: &str) -> Result<usize, Problem> {
+    token.parse().map_err(|_| Problem::NodeId)
+}
+
+// src, dst and the weight if the line has one
+type Line<'a> = (&'a str, &'a str, Option<f64>);
+
+// None for comments (// or # as in SNAP files) and empty lines
+fn parse_line(line: &str) -> Result<Option<Line<'_>>, Problem> {
+    if line.trim().is_empty() || line.starts_with("//") || line.starts_with('#') {
+        return Ok(None);
+    }
+
+    let mut tokens = line.split_whitespace();
+    let (Some(src), Some(dst)) = (tokens.next(), tokens.next()) else {
+        return Err(Problem::Columns);
+    };
+    let weight = tokens.next();
+    if tokens.next().is_some() {
+        return Err(Problem::Columns);
+    }
+
+    let weight: Option<f64> = match weight {
+        Some(w) => Some(w.parse().map_err(|_| Problem::Weight)?),
+        None => None,
+    };
+    if let Some(w) = weight
+        && (!w.is_finite() || w < 0.0)
+    {
+        return Err(Problem::Weight);
+    }
+
+    Ok(Some((src, dst, weight)))
+}
+
+// about `parts` pieces of text, every piece ends right after a newline (or at the end)
+fn split_lines(text: &str, parts: usize) -> Vec<&str> {
+    let bytes = text.as_bytes();
+    let mut chunks = Vec::with_capacity(parts);
+    let mut start = 0;
+
+    for i in 1..=parts {
+        if start >= bytes.len() {
+            break;
+        }
+        let target = (bytes.len() * i / parts).max(start);
+        let end = match bytes[target..].iter().position(|&b| b == b'\n') {
+            Some(p) if i < parts => target + p + 1,
+            _ => bytes.len(),
+        };
+        chunks.push(&text[start..end]);
+        start = end;
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_lines() {
+        let text = "0 1\n1 2\n2 3\n3 4\n";
+
+        for parts in [1, 2, 3, 7, 100] {
+            let chunks = split_lines(text, parts);
+            assert_eq!(chunks.concat(), text);
+            assert!(chunks.iter().all(|c| c.ends_with('\n')));
+        }
+        assert!(split_lines("", 4).is_empty());
+    }
+
+    #[test]
+    fn test_parallel_vs_sequential() {
+        let path = "test_edge_list.txt";
+        let mut text = String::from("// header\n# Nodes: 1013 Edges: 5001\n\n");
+        for i in 0..5000 {
+            text.push_str(&format!("{} {}\n", i % 97, (i * 31) % 1013));
+            if i % 10 == 0 {
+                text.push_str("a b c d\n");
+            }
+        }
+        text.push_str("5 6 2.5"); // weighted, no trailing newline
+        std::fs::write(path, text).unwrap();
+
+        let (seq, seq_problems) = read_edge_list(path, |t| t.parse().unwrap()).unwrap();
+        let (par, max_node, problems) = parse_edge_list(path).unwrap();
+
+        assert_eq!(par.edges, seq.edges);
+        assert_eq!(par.weights, seq.weights);
+        assert_eq!(par.edges.len(), 5001);
+        assert_eq!(max_node, 1012);
+
+        // the "a b c d" lines, with the same line numbers both ways
+        assert_eq!(problems, seq_problems);
+        assert_eq!(problems.count, 500);
+        assert_eq!(problems.by_kind[&Problem::Columns], 500);
+        assert_eq!(problems.lines[0].line, 5);
+        assert_eq!(problems.lines[1].line, 16);
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let chunk = parse_chunk("0 1\n1 2 -3.0\n0 x\n\n// 0 x\n0 1 abc\n0 1 2 3\n7\n2 3\n");
+
+        assert_eq!(chunk.edges, vec![(0, 1), (2, 3)]);
+        assert_eq!(chunk.lines, 9);
+        let found: Vec<(usize, Problem)> = chunk
+            .problems
+            .lines
+            .iter()
+            .map(|e| (e.line, e.problem))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (2, Problem::Weight),
+                (3, Problem::NodeId),
+                (6, Problem::Weight),
+                (7, Problem::Columns),
+                (8, Problem::Columns),
+            ]
+        );
+    }
+}
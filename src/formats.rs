@@ -0,0 +1,1087 @@
+use crate::binary::{is_binary, load_binary, save_binary};
+use crate::edge_list::EdgeList;
+use crate::graph::Graph;
+use crate::load_error::{LoadError, Problem, Problems};
+use quick_xml::Reader;
+use quick_xml::XmlVersion;
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GraphFormat {
+    // "src dst [weight]" lines, // and # comments
+    EdgeList,
+    // edge list with # header, tab separated
+    Snap,
+    // .mtx, coordinate format, 1-based
+    MatrixMarket,
+    // .gr, "p sp n m" and "a u v w" lines, 1-based
+    Dimacs,
+    // adjacency lists of an undirected graph, 1-based
+    Metis,
+    GraphMl,
+    // binary CSR written by convert (see binary.rs)
+    Binary,
+}
+
+impl GraphFormat {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "edgelist" | "edges" | "txt" => Some(GraphFormat::EdgeList),
+            "snap" => Some(GraphFormat::Snap),
+            "mtx" | "matrix-market" => Some(GraphFormat::MatrixMarket),
+            "dimacs" | "gr" => Some(GraphFormat::Dimacs),
+            "metis" => Some(GraphFormat::Metis),
+            "graphml" => Some(GraphFormat::GraphMl),
+            "binary" | "bin" => Some(GraphFormat::Binary),
+            _ => None,
+        }
+    }
+
+    // None when the extension says nothing (.txt, .edges, no extension)
+    pub fn from_path(path: &str) -> Option<Self> {
+        let ext = path.rsplit_once('.')?.1.to_lowercase();
+        match ext.as_str() {
+            "snap" => Some(GraphFormat::Snap),
+            "mtx" => Some(GraphFormat::MatrixMarket),
+            "gr" => Some(GraphFormat::Dimacs),
+            "metis" | "graph" => Some(GraphFormat::Metis),
+            "graphml" => Some(GraphFormat::GraphMl),
+            "bin" => Some(GraphFormat::Binary),
+            _ => None,
+        }
+    }
+}
+
+// binary files are recognized by their header, the rest by extension, edge list otherwise
+pub fn detect_input(path: &str) -> GraphFormat {
+    if is_binary(path) {
+        return GraphFormat::Binary;
+    }
+    GraphFormat::from_path(path).unwrap_or(GraphFormat::EdgeList)
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct LoadOptions {
+    // keep the ids of the file in results: any token for edge lists,
+    // 1-based ids for Matrix Market, DIMACS and METIS (GraphML ids are always kept)
+    pub relabel: bool,
+    // skip bad lines and return them with the graph instead of failing
+    pub lenient: bool,
+    // symmetrize after loading, see Graph::symmetrize
+    pub undirected: bool,
+    // binary files: checksum everything instead of only the header, see load_binary
+    pub verify: bool,
+}
+
+// the graph and the lines that were skipped (always empty unless lenient)
+pub fn read_graph(
+    path: &str,
+    format: GraphFormat,
+    options: &LoadOptions,
+) -> Result<(Graph, Problems), LoadError> {
+    let (mut graph, skipped) = match format {
+        GraphFormat::EdgeList | GraphFormat::Snap => Graph::read_file(path, options.relabel)?,
+        GraphFormat::Binary => (load_binary(path, options.verify)?, Problems::default()),
+        GraphFormat::GraphMl => (read_graphml(path)?, Problems::default()),
+        GraphFormat::MatrixMarket | GraphFormat::Dimacs | GraphFormat::Metis => {
+            let (num_nodes, list, skipped) = match format {
+                GraphFormat::MatrixMarket => read_matrix_market(path)?,
+                GraphFormat::Dimacs => read_dimacs(path)?,
+                _ => read_metis(path)?,
+            };
+            let labels = options
+                .relabel
+                .then(|| (1..=num_nodes).map(|i| i.to_string()).collect());
+            (Graph::from_edge_list(num_nodes, &list, labels), skipped)
+        }
+    };
+
+    if options.undirected {
+        graph.symmetrize();
+    }
+
+    if !options.lenient {
+        skipped.check(path)?;
+        return Ok((graph, Problems::default()));
+    }
+    Ok((graph, skipped))
+}
+
+pub fn write_graph(graph: &Graph, path: &str, format: GraphFormat) -> Result<(), Box<dyn Error>> {
+    if format == GraphFormat::Binary {
+        return save_binary(graph, path);
+    }
+
+    let mut w = BufWriter::new(File::create(path)?);
+    match format {
+        GraphFormat::EdgeList => write_edge_list(graph, &mut w, " ")?,
+        GraphFormat::Snap => {
+            writeln!(w, "# Directed graph: {}", path)?;
+            writeln!(
+                w,
+                "# Nodes: {} Edges: {}",
+                graph.num_nodes,
+                graph.num_edges()
+            )?;
+            writeln!(w, "# FromNodeId\tToNodeId")?;
+            write_edge_list(graph, &mut w, "\t")?;
+        }
+        GraphFormat::MatrixMarket => write_matrix_market(graph, &mut w)?,
+        GraphFormat::Dimacs => write_dimacs(graph, &mut w)?,
+        GraphFormat::Metis => write_metis(graph, &mut w)?,
+        GraphFormat::GraphMl => write_graphml(graph, &mut w)?,
+        GraphFormat::Binary => unreachable!(),
+    }
+    w.flush()?;
+
+    Ok(())
+}
+
+fn check_weight(weight: f64) -> Result<f64, Problem> {
+    if !weight.is_finite() || weight < 0.0 {
+        return Err(Problem::Weight);
+    }
+    Ok(weight)
+}
+
+fn parse_weight(token: Option<&&str>) -> Result<f64, Problem> {
+    let token = token.ok_or(Problem::Columns)?;
+    check_weight(token.parse().map_err(|_| Problem::Weight)?)
+}
+
+// 1..=n in the file, 0..n in the graph
+fn one_based(token: Option<&&str>, n: usize) -> Result<usize, Problem> {
+    let token = token.ok_or(Problem::Columns)?;
+    match token.parse::<usize>() {
+        Ok(id) if id >= 1 && id <= n => Ok(id - 1),
+        Ok(_) => Err(Problem::OutOfRange),
+        Err(_) => Err(Problem::NodeId),
+    }
+}
+
+// (line number, line), io errors carry the path
+fn numbered_lines(
+    path: &str,
+) -> Result<impl Iterator<Item = Result<(usize, String), LoadError>> + '_, LoadError> {
+    let file = File::open(path).map_err(|e| LoadError::io(path, e))?;
+    let lines = BufReader::new(file).lines().enumerate();
+    Ok(lines.map(move |(i, line)| line.map(|l| (i + 1, l)).map_err(|e| LoadError::io(path, e))))
+}
+
+// a number in a header line, a bad header fails the whole file
+fn header_value(
+    path: &str,
+    line: usize,
+    token: Option<&&str>,
+    what: &str,
+) -> Result<usize, LoadError> {
+    token
+        .and_then(|t| t.parse().ok())
+        .ok_or_else(|| LoadError::format(path, Some(line), format!("bad {}", what)))
+}
+
+// counts from a header are only a hint: a wrong one must not reserve more entries
+// than the file can hold when every entry takes at least min_bytes
+fn capacity_hint(path: &str, count: usize, min_bytes: usize) -> usize {
+    let file_len = std::fs::metadata(path).map_or(0, |m| m.len() as usize);
+    count.min(file_len / min_bytes)
+}
+
+// a header may declare isolated nodes that no line mentions, so unlike counts of entries
+// the node count can't be capped by the file size; it is only refused if its arrays
+// can't be allocated at all
+fn node_count(path: &str, line: usize, n: usize) -> Result<usize, LoadError> {
+    let mut probe: Vec<usize> = Vec::new();
+    probe.try_reserve_exact(n.saturating_add(1)).map_err(|_| {
+        LoadError::format(
+            path,
+            Some(line),
+            format!("header says {} nodes, more than fit in memory", n),
+        )
+    })?;
+    Ok(n)
+}
+
+fn read_matrix_market(path: &str) -> Result<(usize, EdgeList, Problems), LoadError> {
+    let mut lines = numbered_lines(path)?;
+    let error = |message: String| LoadError::format(path, Some(1), message);
+    let banner = match lines.next() {
+        Some(line) => line?.1.to_lowercase(),
+        None => return Err(LoadError::format(path, None, "empty Matrix Market file")),
+    };
+    let banner: Vec<&str> = banner.split_whitespace().collect();
+    if banner.len() != 5 || banner[..3] != ["%%matrixmarket", "matrix", "coordinate"] {
+        return Err(error(
+            "only '%%MatrixMarket matrix coordinate' files are supported".to_string(),
+        ));
+    }
+    let pattern = match banner[3] {
+        "pattern" => true,
+        "real" | "integer" | "double" => false,
+        field => {
+            return Err(error(format!(
+                "Matrix Market field '{}' is not supported",
+                field
+            )));
+        }
+    };
+    let symmetric = match banner[4] {
+        "general" => false,
+        "symmetric" => true,
+        s => {
+            return Err(error(format!(
+                "Matrix Market symmetry '{}' is not supported",
+                s
+            )));
+        }
+    };
+
+    let mut size = None;
+    let mut edges = Vec::new();
+    let mut weights = Vec::new();
+    let mut problems = Problems::default();
+    for line in lines {
+        let (number, line) = line?;
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.is_empty() || tokens[0].starts_with('%') {
+            continue;
+        }
+
+        let Some((rows, cols)) = size else {
+            let what = "Matrix Market size line";
+            let rows = header_value(path, number, tokens.first(), what)?;
+            let cols = header_value(path, number, tokens.get(1), what)?;
+            let entries = header_value(path, number, tokens.get(2), what)?;
+            node_count(path, number, rows.max(cols))?;
+            // "1 1\n"
+            edges.reserve(capacity_hint(path, entries, 4));
+            size = Some((rows, cols));
+            continue;
+        };
+
+        let entry = |tokens: &[&str]| -> Result<(usize, usize, f64), Problem> {
+            if tokens.len() != if pattern { 2 } else { 3 } {
+                return Err(Problem::Columns);
+            }
+            let i = one_based(tokens.first(), rows)?;
+            let j = one_based(tokens.get(1), cols)?;
+            let weight = if pattern {
+                1.0
+            } else {
+                parse_weight(tokens.get(2))?
+            };
+            Ok((i, j, weight))
+        };
+        let (i, j, weight) = match entry(&tokens) {
+            Ok(entry) => entry,
+            Err(problem) => {
+                problems.push(number, problem, &line);
+                continue;
+            }
+        };
+
+        edges.push((i, j));
+        weights.push(weight);
+        if symmetric && i != j {
+            edges.push((j, i));
+            weights.push(weight);
+        }
+    }
+
+    let (rows, cols) =
+        size.ok_or_else(|| LoadError::format(path, None, "Matrix Market file has no size line"))?;
+    let list = EdgeList {
+        edges,
+        weights: (!pattern).then_some(weights),
+    };
+    Ok((rows.max(cols), list, problems))
+}
+
+// shortest path ("p sp", "a u v w") and plain ("p edge", "e u v") DIMACS files
+fn read_dimacs(path: &str) -> Result<(usize, EdgeList, Problems), LoadError> {
+    let mut num_nodes = None;
+    let mut edges = Vec::new();
+    let mut weights = Vec::new();
+    let mut weighted = false;
+    let mut problems = Problems::default();
+
+    for line in numbered_lines(path)? {
+        let (number, line) = line?;
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens.first() {
+            None | Some(&"c") => continue,
+            Some(&"p") => {
+                let what = "DIMACS problem line";
+                let n = node_count(
+                    path,
+                    number,
+                    header_value(path, number, tokens.get(2), what)?,
+                )?;
+                let m = header_value(path, number, tokens.get(3), what)?;
+                // "e 1 1\n"
+                edges.reserve(capacity_hint(path, m, 6));
+                num_nodes = Some(n);
+            }
+            Some(&kind @ ("a" | "e")) => {
+                let n = num_nodes.ok_or_else(|| {
+                    LoadError::format(path, Some(number), "DIMACS edge before the 'p' line")
+                })?;
+                let arc = kind == "a";
+                let entry = || -> Result<(usize, usize, f64), Problem> {
+                    if tokens.len() != if arc { 4 } else { 3 } {
+                        return Err(Problem::Columns);
+                    }
+                    let u = one_based(tokens.get(1), n)?;
+                    let v = one_based(tokens.get(2), n)?;
+                    let weight = if arc {
+                        parse_weight(tokens.get(3))?
+                    } else {
+                        1.0
+                    };
+                    Ok((u, v, weight))
+                };
+                match entry() {
+                    Ok((u, v, weight)) => {
+                        weighted |= arc;
+                        edges.push((u, v));
+                        weights.push(weight);
+                    }
+                    Err(problem) => problems.push(number, problem, &line),
+                }
+            }
+            Some(_) => problems.push(number, Problem::LineType, &line),
+        }
+    }
+
+    let num_nodes =
+        num_nodes.ok_or_else(|| LoadError::format(path, None, "DIMACS file has no 'p' line"))?;
+    let list = EdgeList {
+        edges,
+        weights: weighted.then_some(weights),
+    };
+    Ok((num_nodes, list, problems))
+}
+
+// header "n m [fmt [ncon]]", then one line per vertex (empty line = no neighbors)
+fn read_metis(path: &str) -> Result<(usize, EdgeList, Problems), LoadError> {
+    let lines = numbered_lines(path)?;
+    let mut lines = lines.filter(|l| !l.as_ref().is_ok_and(|(_, l)| l.starts_with('%')));
+
+    let (number, header) = loop {
+        let Some(line) = lines.next() else {
+            return Err(LoadError::format(path, None, "empty METIS file"));
+        };
+        let (number, line) = line?;
+        if !line.trim().is_empty() {
+            break (number, line);
+        }
+    };
+    let header: Vec<&str> = header.split_whitespace().collect();
+    let what = "METIS header";
+    let n = header_value(path, number, header.first(), what)?;
+    let m = header_value(path, number, header.get(1), what)?;
+    // fmt digits: vertex sizes, vertex weights, edge weights
+    let fmt = header.get(2).unwrap_or(&"0");
+    if fmt.len() > 3 || !fmt.bytes().all(|b| b == b'0' || b == b'1') {
+        return Err(LoadError::format(
+            path,
+            Some(number),
+            format!("bad METIS fmt '{}' (up to 3 digits, each 0 or 1)", fmt),
+        ));
+    }
+    let fmt = format!("{:0>3}", fmt);
+    let sizes = fmt.as_bytes()[0] == b'1';
+    let edge_weights = fmt.as_bytes()[2] == b'1';
+    let ncon = match header.get(3) {
+        Some(_) => header_value(path, number, header.get(3), what)?,
+        None => usize::from(fmt.as_bytes()[1] == b'1'),
+    };
+    let skip = usize::from(sizes) + ncon;
+
+    // every edge is in two lists, "1 " at least
+    let capacity = capacity_hint(path, m.saturating_mul(2), 2);
+    let mut edges = Vec::with_capacity(capacity);
+    let mut weights = Vec::with_capacity(capacity);
+    let mut problems = Problems::default();
+    for u in 0..n {
+        let Some(line) = lines.next() else {
+            return Err(LoadError::format(
+                path,
+                None,
+                format!("METIS file has {} vertex lines, header says {}", u, n),
+            ));
+        };
+        let (number, line) = line?;
+        let tokens: Vec<&str> = line.split_whitespace().skip(skip).collect();
+        let step = if edge_weights { 2 } else { 1 };
+
+        for entry in tokens.chunks(step) {
+            let parsed = one_based(entry.first(), n).and_then(|v| match edge_weights {
+                true => Ok((v, parse_weight(entry.get(1))?)),
+                false => Ok((v, 1.0)),
+            });
+            match parsed {
+                Ok((v, weight)) => {
+                    edges.push((u, v));
+                    weights.push(weight);
+                }
+                Err(problem) => problems.push(number, problem, &line),
+            }
+        }
+    }
+
+    // skipped entries would always break the count
+    if problems.is_empty() && m.checked_mul(2) != Some(edges.len()) {
+        return Err(LoadError::format(
+            path,
+            None,
+            format!(
+                "METIS header says {} edges but the adjacency lists hold {} entries (expected {})",
+                m,
+                edges.len(),
+                2 * m as u128
+            ),
+        ));
+    }
+
+    let list = EdgeList {
+        edges,
+        weights: edge_weights.then_some(weights),
+    };
+    Ok((n, list, problems))
+}
+
+fn attribute(e: &BytesStart, name: &str) -> Result<Option<String>, Box<dyn Error>> {
+    for attr in e.attributes() {
+        let attr = attr?;
+        if attr.key.local_name().as_ref() == name {
+            return Ok(Some(
+                attr.normalized_value(XmlVersion::Implicit1_0)?.into_owned(),
+            ));
+        }
+    }
+    Ok(None)
+}
+
+// node ids of GraphML are strings, they are kept as labels
+fn read_graphml(path: &str) -> Result<Graph, LoadError> {
+    let file = File::open(path).map_err(|e| LoadError::io(path, e))?;
+    let mut reader = Reader::from_reader(BufReader::new(file));
+    parse_graphml(&mut reader).map_err(|e| {
+        let message = format!("{} (at byte {})", e, reader.buffer_position());
+        LoadError::format(path, None, message)
+    })
+}
+
+fn parse_graphml(reader: &mut Reader<BufReader<File>>) -> Result<Graph, Box<dyn Error>> {
+    let mut buf = Vec::new();
+
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut labels: Vec<String> = Vec::new();
+    let mut node_id = |id: String| -> usize {
+        *index.entry(id).or_insert_with_key(|id| {
+            labels.push(id.clone());
+            labels.len() - 1
+        })
+    };
+
+    let mut weight_key: Option<String> = None;
+    let mut default_directed = true;
+    // (src, dst, weight, directed)
+    let mut edges: Vec<(usize, usize, f64, bool)> = Vec::new();
+    let mut in_edge = false;
+    let mut in_weight = false;
+    let mut weighted = false;
+
+    loop {
+        let event = reader.read_event_into(&mut buf)?;
+        // <edge> and <data> can have content, <edge/> and <data/> can't
+        let open = matches!(event, Event::Start(_));
+        match event {
+            Event::Eof => break,
+            Event::Start(ref e) | Event::Empty(ref e) if !in_weight => {
+                match e.local_name().as_ref() {
+                    "key" => {
+                        let for_edges =
+                            matches!(attribute(e, "for")?.as_deref(), Some("edge") | Some("all"));
+                        if for_edges && attribute(e, "attr.name")?.as_deref() == Some("weight") {
+                            weight_key = attribute(e, "id")?;
+                        }
+                    }
+                    "graph" => {
+                        default_directed =
+                            attribute(e, "edgedefault")?.as_deref() != Some("undirected");
+                    }
+                    "node" => {
+                        let id = attribute(e, "id")?.ok_or("GraphML node without id")?;
+                        node_id(id);
+                    }
+                    "edge" => {
+                        let src = attribute(e, "source")?.ok_or("GraphML edge without source")?;
+                        let dst = attribute(e, "target")?.ok_or("GraphML edge without target")?;
+                        let directed = match attribute(e, "directed")?.as_deref() {
+                            Some("true") => true,
+                            Some("false") => false,
+                            _ => default_directed,
+                        };
+                        edges.push((node_id(src), node_id(dst), 1.0, directed));
+                        in_edge = open;
+                    }
+                    "data" => {
+                        in_weight = open
+                            && in_edge
+                            && weight_key.is_some()
+                            && attribute(e, "key")? == weight_key;
+                    }
+                    _ => {}
+                }
+            }
+            Event::Text(text) if in_weight => {
+                let weight = text.trim();
+                let weight = (weight.parse().ok())
+                    .and_then(|w| check_weight(w).ok())
+                    .ok_or_else(|| format!("invalid edge weight '{}'", weight))?;
+                edges.last_mut().unwrap().2 = weight;
+                weighted = true;
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                "edge" => in_edge = false,
+                "data" => in_weight = false,
+                _ => {}
+            },
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    let mut list = EdgeList {
+        edges: Vec::with_capacity(edges.len()),
+        weights: None,
+    };
+    let mut weights = Vec::with_capacity(edges.len());
+    for (src, dst, weight, directed) in edges {
+        list.edges.push((src, dst));
+        weights.push(weight);
+        if !directed && src != dst {
+            list.edges.push((dst, src));
+            weights.push(weight);
+        }
+    }
+    list.weights = weighted.then_some(weights);
+
+    Ok(Graph::from_edge_list(labels.len(), &list, Some(labels)))
+}
+
+// text of every node's out-edges, built in parallel blocks of nodes
+fn edge_lines<F>(graph: &Graph, line: F) -> Vec<String>
+where
+    F: Fn(&mut String, usize, usize, f64) + Sync,
+{
+    const BLOCK: usize = 4096;
+
+    (0..graph.num_nodes.div_ceil(BLOCK))
+        .into_par_iter()
+        .map(|b| {
+            let mut text = String::new();
+            for u in b * BLOCK..((b + 1) * BLOCK).min(graph.num_nodes) {
+                for (v, w) in graph.weighted_neighbors(u) {
+                    line(&mut text, u, v, w);
+                }
+            }
+            text
+        })
+        .collect()
+}
+
+fn write_blocks(w: &mut dyn Write, blocks: Vec<String>) -> std::io::Result<()> {
+    for block in blocks {
+        w.write_all(block.as_bytes())?;
+    }
+    Ok(())
+}
+
+fn write_edge_list(
+    graph: &Graph,
+    w: &mut dyn Write,
+    separator: &str,
+) -> Result<(), Box<dyn Error>> {
+    use std::fmt::Write;
+
+    if let Some(bad) = graph.labels().and_then(|labels| {
+        labels
+            .iter()
+            .find(|l| l.contains(char::is_whitespace) || l.is_empty())
+    }) {
+        return Err(format!("node id '{}' cannot be written to an edge list", bad).into());
+    }
+
+    let weighted = graph.is_weighted();
+    let blocks = edge_lines(graph, |text, u, v, weight| {
+        match graph.labels() {
+            Some(labels) => write!(text, "{}{}{}", labels[u], separator, labels[v]),
+            None => write!(text, "{}{}{}", u, separator, v),
+        }
+        .unwrap();
+        if weighted {
+            write!(text, "{}{}", separator, weight).unwrap();
+        }
+        text.push('\n');
+    });
+    write_blocks(w, blocks)?;
+
+    Ok(())
+}
+
+fn write_matrix_market(graph: &Graph, w: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+    use std::fmt::Write as _;
+
+    let weighted = graph.is_weighted();
+    let field = if weighted { "real" } else { "pattern" };
+    writeln!(w, "%%MatrixMarket matrix coordinate {} general", field)?;
+    writeln!(
+        w,
+        "{} {} {}",
+        graph.num_nodes,
+        graph.num_nodes,
+        graph.num_edges()
+    )?;
+
+    let blocks = edge_lines(graph, |text, u, v, weight| {
+        if weighted {
+            writeln!(text, "{} {} {}", u + 1, v + 1, weight).unwrap();
+        } else {
+            writeln!(text, "{} {}", u + 1, v + 1).unwrap();
+        }
+    });
+    write_blocks(w, blocks)?;
+
+    Ok(())
+}
+
+fn write_dimacs(graph: &Graph, w: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+    use std::fmt::Write as _;
+
+    writeln!(w, "p sp {} {}", graph.num_nodes, graph.num_edges())?;
+    let blocks = edge_lines(graph, |text, u, v, weight| {
+        writeln!(text, "a {} {} {}", u + 1, v + 1, weight).unwrap();
+    });
+    write_blocks(w, blocks)?;
+
+    Ok(())
+}
+
+// METIS graphs are undirected without self-loops: u-v is written if u→v or v→u
+// exists, with the weight of the first such edge
+fn write_metis(graph: &Graph, w: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+    let mut lists: Vec<Vec<(usize, f64)>> = vec![Vec::new(); graph.num_nodes];
+    for u in 0..graph.num_nodes {
+        for (v, weight) in graph.weighted_neighbors(u) {
+            if u != v {
+                lists[u].push((v, weight));
+                lists[v].push((u, weight));
+            }
+        }
+    }
+    lists.par_iter_mut().for_each(|list| {
+        list.sort_by_key(|&(v, _)| v);
+        list.dedup_by_key(|&mut (v, _)| v);
+    });
+
+    let entries: usize = lists.iter().map(Vec::len).sum();
+    let weighted = graph.is_weighted();
+    if weighted {
+        writeln!(w, "{} {} 001", graph.num_nodes, entries / 2)?;
+    } else {
+        writeln!(w, "{} {}", graph.num_nodes, entries / 2)?;
+    }
+
+    for list in lists {
+        let line: Vec<String> = list
+            .iter()
+            .map(|&(v, weight)| {
+                if weighted {
+                    format!("{} {}", v + 1, weight)
+                } else {
+                    (v + 1).to_string()
+                }
+            })
+            .collect();
+        writeln!(w, "{}", line.join(" "))?;
+    }
+
+    Ok(())
+}
+
+fn write_graphml(graph: &Graph, w: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+    use std::fmt::Write as _;
+
+    let id = |u: usize| match graph.labels() {
+        Some(labels) => escape(labels[u].as_str()).into_owned(),
+        None => u.to_string(),
+    };
+
+    writeln!(w, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(
+        w,
+        "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">"
+    )?;
+    let weighted = graph.is_weighted();
+    if weighted {
+        writeln!(
+            w,
+            "  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"double\"/>"
+        )?;
+    }
+    writeln!(w, "  <graph id=\"G\" edgedefault=\"directed\">")?;
+    for u in 0..graph.num_nodes {
+        writeln!(w, "    <node id=\"{}\"/>", id(u))?;
+    }
+
+    let blocks = edge_lines(graph, |text, u, v, weight| {
+        if weighted {
+            writeln!(
+                text,
+                "    <edge source=\"{}\" target=\"{}\"><data key=\"weight\">{}</data></edge>",
+                id(u),
+                id(v),
+                weight
+            )
+            .unwrap();
+        } else {
+            writeln!(
+                text,
+                "    <edge source=\"{}\" target=\"{}\"/>",
+                id(u),
+                id(v)
+            )
+            .unwrap();
+        }
+    });
+    write_blocks(w, blocks)?;
+
+    writeln!(w, "  </graph>")?;
+    writeln!(w, "</graphml>")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges_of(graph: &Graph) -> Vec<(usize, usize, f64)> {
+        (0..graph.num_nodes)
+            .flat_map(|u| graph.weighted_neighbors(u).map(move |(v, w)| (u, v, w)))
+            .collect()
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            GraphFormat::from_path("a/b.MTX"),
+            Some(GraphFormat::MatrixMarket)
+        );
+        assert_eq!(GraphFormat::from_path("road.gr"), Some(GraphFormat::Dimacs));
+        assert_eq!(
+            GraphFormat::from_path("g.graphml"),
+            Some(GraphFormat::GraphMl)
+        );
+        assert_eq!(GraphFormat::from_path("g.txt"), None);
+        assert_eq!(GraphFormat::from_path("graph"), None);
+    }
+
+    #[test]
+    fn test_read_snap() {
+        let path = "test_formats_snap.txt";
+        std::fs::write(
+            path,
+            "# Directed graph: web\n# FromNodeId\tToNodeId\n0\t1\n1\t2\n",
+        )
+        .unwrap();
+
+        let graph = read_graph(path, detect_input(path), &LoadOptions::default())
+            .unwrap()
+            .0;
+        assert_eq!(edges_of(&graph), vec![(0, 1, 1.0), (1, 2, 1.0)]);
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_read_matrix_market() {
+        let path = "test_formats.mtx";
+        std::fs::write(
+            path,
+            "%%MatrixMarket matrix coordinate real symmetric\n% comment\n3 3 2\n2 1 0.5\n3 3 2\n",
+        )
+        .unwrap();
+
+        let graph = read_graph(path, detect_input(path), &LoadOptions::default())
+            .unwrap()
+            .0;
+        assert_eq!(graph.num_nodes, 3);
+        assert_eq!(
+            edges_of(&graph),
+            vec![(0, 1, 0.5), (1, 0, 0.5), (2, 2, 2.0)]
+        );
+
+        let graph = read_graph(
+            path,
+            GraphFormat::MatrixMarket,
+            &LoadOptions {
+                relabel: true,
+                ..LoadOptions::default()
+            },
+        )
+        .unwrap()
+        .0;
+        assert_eq!(graph.label(0), "1");
+
+        std::fs::write(path, "%%MatrixMarket matrix array real general\n2 2\n").unwrap();
+        assert!(read_graph(path, GraphFormat::MatrixMarket, &LoadOptions::default()).is_err());
+
+        std::fs::write(
+            path,
+            "%%MatrixMarket matrix coordinate pattern general\n2 99999999999999999 1\n1 2\n",
+        )
+        .unwrap();
+        let error = read_graph(path, GraphFormat::MatrixMarket, &LoadOptions::default());
+        assert!(error.is_err_and(|e| e.to_string().contains("nodes")));
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_read_dimacs() {
+        let path = "test_formats.gr";
+        std::fs::write(path, "c road\np sp 3 2\na 1 2 7\na 2 3 1\n").unwrap();
+
+        let graph = read_graph(path, detect_input(path), &LoadOptions::default())
+            .unwrap()
+            .0;
+        assert!(graph.is_weighted());
+        assert_eq!(edges_of(&graph), vec![(0, 1, 7.0), (1, 2, 1.0)]);
+
+        std::fs::write(path, "p sp 2 1\na 1 3 1\n").unwrap();
+        assert!(read_graph(path, GraphFormat::Dimacs, &LoadOptions::default()).is_err());
+
+        // the edge count of the header is only used to reserve space
+        std::fs::write(path, "p sp 3 99999999999999\na 1 2 7\n").unwrap();
+        let graph = read_graph(path, GraphFormat::Dimacs, &LoadOptions::default())
+            .unwrap()
+            .0;
+        assert_eq!(graph.num_edges(), 1);
+
+        // isolated nodes are fine, a node count that can't be allocated is a header error
+        std::fs::write(
+            path,
+            "p sp 1000 0
+",
+        )
+        .unwrap();
+        let graph = read_graph(path, GraphFormat::Dimacs, &LoadOptions::default())
+            .unwrap()
+            .0;
+        assert_eq!(graph.num_nodes, 1000);
+        std::fs::write(
+            path,
+            "p sp 99999999999999999 1
+a 1 2 7
+",
+        )
+        .unwrap();
+        let error = read_graph(path, GraphFormat::Dimacs, &LoadOptions::default());
+        assert!(error.is_err_and(|e| e.to_string().contains("nodes")));
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_read_metis() {
+        let path = "test_formats.metis";
+        // triangle 1-2-3 plus isolated vertex 4, vertex weights and edge weights
+        std::fs::write(
+            path,
+            "% comment\n4 3 011\n5 2 1 3 2\n5 1 1 3 4\n5 1 2 2 4\n5\n",
+        )
+        .unwrap();
+
+        let graph = read_graph(path, detect_input(path), &LoadOptions::default())
+            .unwrap()
+            .0;
+        assert_eq!(graph.num_nodes, 4);
+        assert_eq!(graph.neighbors(0), &[1, 2]);
+        assert_eq!(graph.neighbors(3), &[] as &[usize]);
+        assert_eq!(
+            graph.weighted_neighbors(1).collect::<Vec<_>>(),
+            vec![(0, 1.0), (2, 4.0)]
+        );
+
+        std::fs::write(path, "3 2\n2\n1\n\n").unwrap();
+        assert!(read_graph(path, GraphFormat::Metis, &LoadOptions::default()).is_err());
+
+        // fmt "0011" would be read by position as vertex weights only
+        for fmt in ["0011", "012", "1x"] {
+            std::fs::write(path, format!("2 1 {}\n2\n1\n", fmt)).unwrap();
+            let error = read_graph(path, GraphFormat::Metis, &LoadOptions::default());
+            assert!(error.is_err_and(|e| e.to_string().contains("fmt")));
+        }
+
+        // a header that lies about the size fails the count, it doesn't allocate for it
+        std::fs::write(path, "2 9223372036854775807\n2\n1\n").unwrap();
+        assert!(read_graph(path, GraphFormat::Metis, &LoadOptions::default()).is_err());
+        // every node needs a line, so a huge node count runs out of lines first
+        std::fs::write(path, "99999999999999999 1\n2\n1\n").unwrap();
+        let error = read_graph(path, GraphFormat::Metis, &LoadOptions::default());
+        assert!(error.is_err_and(|e| e.to_string().contains("vertex lines")));
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_read_graphml() {
+        let path = "test_formats.graphml";
+        std::fs::write(
+            path,
+            r#"<?xml version="1.0"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="d0" for="edge" attr.name="weight" attr.type="double"/>
+  <graph edgedefault="undirected">
+    <node id="pump &amp; valve"/>
+    <node id="sensor"/>
+    <edge source="pump &amp; valve" target="sensor"><data key="d0">2.5</data></edge>
+    <edge source="sensor" target="alarm" directed="true"/>
+  </graph>
+</graphml>"#,
+        )
+        .unwrap();
+
+        let graph = read_graph(path, detect_input(path), &LoadOptions::default())
+            .unwrap()
+            .0;
+        assert_eq!(
+            graph.labels().unwrap(),
+            &[
+                "pump & valve".to_string(),
+                "sensor".to_string(),
+                "alarm".to_string()
+            ]
+        );
+        assert_eq!(
+            edges_of(&graph),
+            vec![(0, 1, 2.5), (1, 0, 2.5), (1, 2, 1.0)]
+        );
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_strict_and_lenient() {
+        let lenient = LoadOptions {
+            lenient: true,
+            ..LoadOptions::default()
+        };
+
+        let path = "test_formats_bad.txt";
+        std::fs::write(path, "# header\n0 1\n1 x\n2 3 -1\n3\n1 2\n").unwrap();
+        let error = read_graph(path, GraphFormat::EdgeList, &LoadOptions::default())
+            .err()
+            .unwrap();
+        let LoadError::Lines { problems, .. } = &error else {
+            panic!("{}", error);
+        };
+        let lines: Vec<usize> = problems.lines.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![3, 4, 5]);
+        assert!(
+            error
+                .to_string()
+                .contains(":3: node id is not a number '1 x'")
+        );
+
+        let (graph, skipped) = read_graph(path, GraphFormat::EdgeList, &lenient).unwrap();
+        assert_eq!(edges_of(&graph), vec![(0, 1, 1.0), (1, 2, 1.0)]);
+        assert_eq!(skipped.count, 3);
+        assert_eq!(skipped.by_kind[&Problem::Columns], 1);
+        std::fs::remove_file(path).ok();
+
+        // every bad line is reported, not only the first few
+        let text: String = (0..50).map(|i| format!("{} x\n", i)).collect();
+        std::fs::write(path, text).unwrap();
+        let error = read_graph(path, GraphFormat::EdgeList, &LoadOptions::default())
+            .err()
+            .unwrap();
+        let LoadError::Lines { problems, .. } = &error else {
+            panic!("{}", error);
+        };
+        assert_eq!(problems.lines.len(), 50);
+        assert_eq!(problems.lines[49].line, 50);
+        assert!(
+            error
+                .to_string()
+                .contains(":50: node id is not a number '49 x'")
+        );
+        std::fs::remove_file(path).ok();
+
+        let path = "test_formats_bad.gr";
+        std::fs::write(path, "c x\np sp 3 2\na 1 2 7\na 1 9 1\nq\na 2 3 1\n").unwrap();
+        assert!(read_graph(path, GraphFormat::Dimacs, &LoadOptions::default()).is_err());
+        let (graph, skipped) = read_graph(path, GraphFormat::Dimacs, &lenient).unwrap();
+        assert_eq!(graph.num_edges(), 2);
+        let found: Vec<(usize, Problem)> =
+            skipped.lines.iter().map(|e| (e.line, e.problem)).collect();
+        assert_eq!(
+            found,
+            vec![(4, Problem::OutOfRange), (5, Problem::LineType)]
+        );
+
+        // a broken header fails even a lenient load
+        std::fs::write(path, "p sp x 2\n").unwrap();
+        let error = read_graph(path, GraphFormat::Dimacs, &lenient)
+            .err()
+            .unwrap();
+        assert!(matches!(error, LoadError::Format { line: Some(1), .. }));
+        std::fs::remove_file(path).ok();
+
+        let error = read_graph("missing.txt", GraphFormat::EdgeList, &lenient)
+            .err()
+            .unwrap();
+        assert!(matches!(error, LoadError::Io { .. }));
+    }
+
+    #[test]
+    fn test_write_read_roundtrip() {
+        let graph = Graph::from_weighted_edges(4, &[(0, 1, 1.5), (2, 0, 3.0), (3, 3, 1.0)]);
+
+        for (format, path) in [
+            (GraphFormat::EdgeList, "test_roundtrip.txt"),
+            (GraphFormat::Snap, "test_roundtrip_snap.txt"),
+            (GraphFormat::MatrixMarket, "test_roundtrip.mtx"),
+            (GraphFormat::Dimacs, "test_roundtrip.gr"),
+            (GraphFormat::GraphMl, "test_roundtrip.graphml"),
+            (GraphFormat::Binary, "test_roundtrip.bin"),
+        ] {
+            write_graph(&graph, path, format).unwrap();
+            let loaded = read_graph(path, format, &LoadOptions::default()).unwrap().0;
+            assert_eq!(edges_of(&loaded), edges_of(&graph), "{:?}", format);
+            std::fs::remove_file(path).ok();
+        }
+
+        // undirected, no self-loops
+        let path = "test_roundtrip.metis";
+        write_graph(&graph, path, GraphFormat::Metis).unwrap();
+        let loaded = read_graph(path, GraphFormat::Metis, &LoadOptions::default())
+            .unwrap()
+            .0;
+        assert_eq!(
+            edges_of(&loaded),
+            vec![(0, 1, 1.5), (0, 2, 3.0), (1, 0, 1.5), (2, 0, 3.0)]
+        );
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -1,41 +1,764 @@
-use std::error::Error;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use crate::binary::Array;
+use crate::edge_list::{EdgeList, parse_edge_list, read_edge_list};
+use crate::load_error::{LoadError, Problems};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 
+// CSR (compressed sparse row): neighbors of u are targets[offsets[u]..offsets[u + 1]]
+// arrays are in memory or mapped from a binary graph file (see binary.rs)
 pub struct Graph {
     pub num_nodes: usize,
-    pub edges: Vec<Vec<usize>>,
+    offsets: Array<usize>,
+    targets: Array<usize>,
+    // weight of every edge, same layout as targets
+    weights: Option<Array<f64>>,
+    reverse: Option<Reverse>,
+    // original id of every node when the graph was loaded with relabeling
+    labels: Option<Vec<String>>,
+}
+
+// preprocessing done by Graph::clean
+#[derive(Clone, Copy, Default)]
+pub struct CleanOptions {
+    pub sort: bool,
+    pub dedup: bool,
+    pub self_loops: bool,
+    pub isolated: bool,
+}
+
+impl CleanOptions {
+    pub fn any(&self) -> bool {
+        self.sort || self.dedup || self.self_loops || self.isolated
+    }
+}
+
+// what Graph::clean removed: edges for self-loops and duplicates, nodes for isolated
+#[derive(Debug, Default, PartialEq)]
+pub struct CleanReport {
+    pub self_loops: usize,
+    pub duplicates: usize,
+    pub isolated: usize,
+}
+
+// transposed CSR, in-neighbors of v are sources[offsets[v]..offsets[v + 1]]
+struct Reverse {
+    offsets: Array<usize>,
+    sources: Array<usize>,
+    weights: Option<Array<f64>>,
 }
 
 impl Graph {
-    pub fn from_file(path: &str) -> Result<Self, Box<dyn Error>> {
-        let file = File::open(path)?;
-        let reader = BufReader::new(file);
-        let mut edges_temp: Vec<(usize, usize)> = Vec::new();
-        let mut max_node = 0;
-
-        for line in reader.lines() {
-            let line = line?;
-            if line.trim().is_empty() || line.starts_with("//") {
-                continue;
+    // strict: any bad line fails the load
+    #[cfg(test)]
+    pub fn from_file(path: &str) -> Result<Self, LoadError> {
+        let (graph, problems) = Graph::read_file(path, false)?;
+        problems.check(path)?;
+        Ok(graph)
+    }
+
+    #[cfg(test)]
+    pub fn from_file_relabeled(path: &str) -> Result<Self, LoadError> {
+        let (graph, problems) = Graph::read_file(path, true)?;
+        problems.check(path)?;
+        Ok(graph)
+    }
+
+    // bad lines are skipped and returned with the graph
+    // with relabel any token can be a node id ("web-01", 4000000000), ids get dense
+    // indices in order of first appearance and the original ids are kept as labels
+    pub fn read_file(path: &str, relabel: bool) -> Result<(Self, Problems), LoadError> {
+        if !relabel {
+            let (list, max_node, problems) = parse_edge_list(path)?;
+            return Ok((Graph::from_edge_list(max_node + 1, &list, None), problems));
+        }
+
+        let mut index: HashMap<String, usize> = HashMap::new();
+        let mut labels: Vec<String> = Vec::new();
+        let (list, problems) = read_edge_list(path, |token| {
+            if let Some(&id) = index.get(token) {
+                return id;
             }
+            let id = labels.len();
+            labels.push(token.to_string());
+            index.insert(token.to_string(), id);
+            id
+        })?;
+
+        Ok((
+            Graph::from_edge_list(labels.len(), &list, Some(labels)),
+            problems,
+        ))
+    }
+
+    pub fn from_edge_list(num_nodes: usize, list: &EdgeList, labels: Option<Vec<String>>) -> Self {
+        let mut graph = Graph::build(num_nodes, &list.edges, list.weights.as_deref());
+        graph.labels = labels;
+        graph
+    }
+
+    #[cfg(test)]
+    pub fn from_edges(num_nodes: usize, edges: &[(usize, usize)]) -> Self {
+        Graph::build(num_nodes, edges, None)
+    }
 
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() == 2 {
-                let src: usize = parts[0].parse()?;
-                let dst: usize = parts[1].parse()?;
-                edges_temp.push((src, dst));
-                max_node = max_node.max(src).max(dst);
+    #[cfg(test)]
+    pub fn from_weighted_edges(num_nodes: usize, edges: &[(usize, usize, f64)]) -> Self {
+        let pairs: Vec<(usize, usize)> = edges.iter().map(|&(src, dst, _)| (src, dst)).collect();
+        let weights: Vec<f64> = edges.iter().map(|&(_, _, w)| w).collect();
+        Graph::build(num_nodes, &pairs, Some(&weights))
+    }
+
+    // counting sort by source, keeps the input order of edges inside every adjacency list
+    fn build(num_nodes: usize, edges: &[(usize, usize)], weights: Option<&[f64]>) -> Self {
+        // one degree histogram per chunk of edges, so few chunks when the graph is very sparse
+        // (every histogram costs num_nodes), at most one per thread
+        let parts = rayon::current_num_threads()
+            .min(edges.len() / num_nodes.max(1))
+            .max(1);
+        let chunk_len = edges.len().div_ceil(parts).max(1);
+
+        let mut counts: Vec<Vec<usize>> = edges
+            .par_chunks(chunk_len)
+            .map(|chunk| {
+                let mut count = vec![0; num_nodes];
+                for &(src, _) in chunk {
+                    count[src] += 1;
+                }
+                count
+            })
+            .collect();
+
+        let degree: Vec<usize> = (0..num_nodes)
+            .into_par_iter()
+            .map(|u| counts.iter().map(|count| count[u]).sum())
+            .collect();
+        let mut offsets = Vec::with_capacity(num_nodes + 1);
+        offsets.push(0);
+        for d in degree {
+            offsets.push(offsets[offsets.len() - 1] + d);
+        }
+
+        // counts become write cursors: a chunk starts where the chunks before it ended
+        let mut cursor = offsets[..num_nodes].to_vec();
+        for count in &mut counts {
+            count
+                .par_iter_mut()
+                .zip(cursor.par_iter_mut())
+                .for_each(|(count, cursor)| {
+                    let n = *count;
+                    *count = *cursor;
+                    *cursor += n;
+                });
+        }
+
+        // every slot is written by exactly one chunk, the atomics only make that safe to share
+        let slots: Vec<AtomicUsize> = (0..edges.len()).map(|_| AtomicUsize::new(0)).collect();
+        let weight_slots: Option<Vec<AtomicU64>> =
+            weights.map(|_| (0..edges.len()).map(|_| AtomicU64::new(0)).collect());
+        edges
+            .par_chunks(chunk_len)
+            .zip(counts.into_par_iter())
+            .enumerate()
+            .for_each(|(c, (chunk, mut next))| {
+                for (i, &(src, dst)) in chunk.iter().enumerate() {
+                    let slot = next[src];
+                    next[src] += 1;
+                    slots[slot].store(dst, Ordering::Relaxed);
+                    if let (Some(out), Some(weights)) = (&weight_slots, weights) {
+                        out[slot].store(weights[c * chunk_len + i].to_bits(), Ordering::Relaxed);
+                    }
+                }
+            });
+
+        let targets: Vec<usize> = slots.into_iter().map(AtomicUsize::into_inner).collect();
+        let sorted_weights: Option<Vec<f64>> = weight_slots.map(|slots| {
+            slots
+                .into_iter()
+                .map(|w| f64::from_bits(w.into_inner()))
+                .collect()
+        });
+
+        Graph {
+            num_nodes,
+            offsets: offsets.into(),
+            targets: targets.into(),
+            weights: sorted_weights.map(Array::from),
+            reverse: None,
+            labels: None,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn from_adjacency(adjacency: Vec<Vec<usize>>) -> Self {
+        let num_nodes = adjacency.len();
+        let mut offsets = Vec::with_capacity(num_nodes + 1);
+        let mut targets = Vec::with_capacity(adjacency.iter().map(Vec::len).sum());
+
+        offsets.push(0);
+        for list in adjacency {
+            targets.extend(list);
+            offsets.push(targets.len());
+        }
+
+        Graph {
+            num_nodes,
+            offsets: offsets.into(),
+            targets: targets.into(),
+            weights: None,
+            reverse: None,
+            labels: None,
+        }
+    }
+
+    pub fn from_csr(
+        num_nodes: usize,
+        offsets: Array<usize>,
+        targets: Array<usize>,
+        weights: Option<Array<f64>>,
+        labels: Option<Vec<String>>,
+    ) -> Self {
+        Graph {
+            num_nodes,
+            offsets,
+            targets,
+            weights,
+            reverse: None,
+            labels,
+        }
+    }
+
+    // raw CSR arrays: offsets, targets and weights
+    pub fn csr(&self) -> (&[usize], &[usize], Option<&[f64]>) {
+        (&self.offsets, &self.targets, self.weights.as_deref())
+    }
+
+    #[inline]
+    pub fn neighbors(&self, node: usize) -> &[usize] {
+        &self.targets[self.offsets[node]..self.offsets[node + 1]]
+    }
+
+    #[inline]
+    pub fn out_degree(&self, node: usize) -> usize {
+        self.offsets[node + 1] - self.offsets[node]
+    }
+
+    pub fn num_edges(&self) -> usize {
+        self.targets.len()
+    }
+
+    pub fn labels(&self) -> Option<&[String]> {
+        self.labels.as_deref()
+    }
+
+    // original id of a node, as it should be shown to the user
+    pub fn label(&self, node: usize) -> String {
+        match &self.labels {
+            Some(labels) => labels[node].clone(),
+            None => node.to_string(),
+        }
+    }
+
+    // original ids back to node indices
+    pub fn resolve_nodes(&self, names: &[String]) -> Result<Vec<usize>, String> {
+        match &self.labels {
+            Some(labels) => {
+                let index: HashMap<&str, usize> = labels
+                    .iter()
+                    .enumerate()
+                    .map(|(i, label)| (label.as_str(), i))
+                    .collect();
+                names
+                    .iter()
+                    .map(|name| {
+                        index
+                            .get(name.as_str())
+                            .copied()
+                            .ok_or_else(|| format!("node {} doesnt exist", name))
+                    })
+                    .collect()
             }
+            None => names
+                .iter()
+                .map(|name| match name.parse::<usize>() {
+                    Ok(id) if id < self.num_nodes => Ok(id),
+                    _ => Err(format!(
+                        "node {} doesnt exist (max is {})",
+                        name,
+                        self.num_nodes.saturating_sub(1)
+                    )),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn is_weighted(&self) -> bool {
+        self.weights.is_some()
+    }
+
+    // (neighbor, weight) pairs, every edge weighs 1.0 in unweighted graphs
+    #[inline]
+    pub fn weighted_neighbors(&self, node: usize) -> impl Iterator<Item = (usize, f64)> + '_ {
+        let weights = self.weights.as_deref();
+        (self.offsets[node]..self.offsets[node + 1])
+            .map(move |i| (self.targets[i], weights.map_or(1.0, |w| w[i])))
+    }
+
+    // sum of out-edge weights, out-degree for unweighted graphs
+    #[inline]
+    pub fn out_weight(&self, node: usize) -> f64 {
+        match &self.weights {
+            Some(w) => w[self.offsets[node]..self.offsets[node + 1]].iter().sum(),
+            None => self.out_degree(node) as f64,
+        }
+    }
+
+    // builds in-adjacency once, in-neighbors of every node end up sorted by id
+    pub fn build_reverse(&mut self) {
+        if self.reverse.is_some() {
+            return;
         }
 
-        let num_nodes = max_node + 1;
-        let mut edges = vec![Vec::new(); num_nodes];
+        let n = self.num_nodes;
+        let mut offsets = vec![0; n + 1];
+        for &dst in self.targets.iter() {
+            offsets[dst + 1] += 1;
+        }
+        for i in 0..n {
+            offsets[i + 1] += offsets[i];
+        }
 
-        for (src, dst) in edges_temp {
-            edges[src].push(dst);
+        let mut next = offsets.clone();
+        let mut sources = vec![0; self.targets.len()];
+        let mut weights = self.weights.as_ref().map(|_| vec![0.0; self.targets.len()]);
+        for u in 0..n {
+            for (v, w) in self.weighted_neighbors(u) {
+                sources[next[v]] = u;
+                if let Some(weights) = weights.as_mut() {
+                    weights[next[v]] = w;
+                }
+                next[v] += 1;
+            }
         }
 
-        Ok(Graph { num_nodes, edges })
+        self.reverse = Some(Reverse {
+            offsets: offsets.into(),
+            sources: sources.into(),
+            weights: weights.map(Array::from),
+        });
+    }
+
+    // flips every edge, out-edges become in-edges and the other way around
+    pub fn transpose(&mut self) {
+        self.build_reverse();
+        let reverse = self.reverse.as_mut().unwrap();
+
+        std::mem::swap(&mut self.offsets, &mut reverse.offsets);
+        std::mem::swap(&mut self.targets, &mut reverse.sources);
+        std::mem::swap(&mut self.weights, &mut reverse.weights);
+    }
+
+    // adds v→u for every u→v unless v→u is already there, reverse edges keep the weight
+    pub fn symmetrize(&mut self) {
+        self.build_reverse();
+
+        let lists: Vec<Vec<(usize, f64)>> = (0..self.num_nodes)
+            .into_par_iter()
+            .map(|u| {
+                let mut out: Vec<usize> = self.neighbors(u).to_vec();
+                out.sort_unstable();
+
+                let mut list: Vec<(usize, f64)> = self.weighted_neighbors(u).collect();
+                let mut last = None;
+                for (w, weight) in self.weighted_in_neighbors(u) {
+                    if out.binary_search(&w).is_err() && last != Some(w) {
+                        list.push((w, weight));
+                    }
+                    last = Some(w);
+                }
+                list
+            })
+            .collect();
+
+        self.set_lists(lists);
+    }
+
+    // steps run in the order self-loops, duplicates, sorting, isolated nodes
+    // dropped nodes are renumbered, unlabeled graphs get their old ids as labels
+    // so that results still use the ids of the input file
+    pub fn clean(&mut self, options: &CleanOptions) -> CleanReport {
+        let mut report = CleanReport::default();
+
+        if options.self_loops || options.dedup || options.sort {
+            // adjacency list, self-loops removed, duplicates removed
+            let cleaned: Vec<_> = (0..self.num_nodes)
+                .into_par_iter()
+                .map(|u| {
+                    let mut list: Vec<(usize, f64)> = self.weighted_neighbors(u).collect();
+                    let before = list.len();
+                    if options.self_loops {
+                        list.retain(|&(v, _)| v != u);
+                    }
+                    let loops = before - list.len();
+
+                    let before = list.len();
+                    if options.dedup {
+                        // first edge to every target wins, weights of the others are dropped
+                        let mut order: Vec<(usize, usize)> =
+                            list.iter().enumerate().map(|(i, &(v, _))| (v, i)).collect();
+                        order.sort_unstable();
+                        order.dedup_by_key(|&mut (v, _)| v);
+                        if !options.sort {
+                            order.sort_unstable_by_key(|&(_, i)| i);
+                        }
+                        list = order.into_iter().map(|(_, i)| list[i]).collect();
+                    } else if options.sort {
+                        list.sort_by_key(|&(v, _)| v);
+                    }
+
+                    let duplicates = before - list.len();
+                    (list, loops, duplicates)
+                })
+                .collect();
+
+            report.self_loops = cleaned.iter().map(|c| c.1).sum();
+            report.duplicates = cleaned.iter().map(|c| c.2).sum();
+            self.set_lists(cleaned.into_iter().map(|c| c.0).collect());
+        }
+
+        if options.isolated {
+            let has_in: Vec<AtomicBool> = (0..self.num_nodes)
+                .map(|_| AtomicBool::new(false))
+                .collect();
+            self.targets.par_iter().for_each(|&v| {
+                has_in[v].store(true, Ordering::Relaxed);
+            });
+            let keep: Vec<bool> = (0..self.num_nodes)
+                .into_par_iter()
+                .map(|u| self.out_degree(u) > 0 || has_in[u].load(Ordering::Relaxed))
+                .collect();
+
+            report.isolated = keep.iter().filter(|&&k| !k).count();
+            if report.isolated > 0 {
+                self.keep_nodes(&keep);
+            }
+        }
+
+        report
+    }
+
+    // removes the nodes that are not kept, the rest keep their order
+    fn keep_nodes(&mut self, keep: &[bool]) {
+        let mut new_id = vec![usize::MAX; self.num_nodes];
+        let mut kept = Vec::new();
+        for u in (0..self.num_nodes).filter(|&u| keep[u]) {
+            new_id[u] = kept.len();
+            kept.push(u);
+        }
+
+        let lists: Vec<Vec<(usize, f64)>> = kept
+            .par_iter()
+            .map(|&u| {
+                self.weighted_neighbors(u)
+                    .map(|(v, w)| (new_id[v], w))
+                    .collect()
+            })
+            .collect();
+        let labels = kept.iter().map(|&u| self.label(u)).collect();
+
+        self.set_lists(lists);
+        self.labels = Some(labels);
+    }
+
+    // replaces the CSR with the given adjacency lists, weights are dropped if the graph had none
+    fn set_lists(&mut self, lists: Vec<Vec<(usize, f64)>>) {
+        let mut offsets = Vec::with_capacity(lists.len() + 1);
+        let mut targets = Vec::with_capacity(lists.iter().map(Vec::len).sum());
+        let mut weights = self
+            .weights
+            .as_ref()
+            .map(|_| Vec::with_capacity(targets.capacity()));
+
+        offsets.push(0);
+        for list in &lists {
+            for &(v, w) in list {
+                targets.push(v);
+                if let Some(weights) = weights.as_mut() {
+                    weights.push(w);
+                }
+            }
+            offsets.push(targets.len());
+        }
+
+        self.num_nodes = lists.len();
+        self.offsets = offsets.into();
+        self.targets = targets.into();
+        self.weights = weights.map(Array::from);
+        self.reverse = None;
+    }
+
+    pub fn has_reverse(&self) -> bool {
+        self.reverse.is_some()
+    }
+
+    #[inline]
+    pub fn in_neighbors(&self, node: usize) -> &[usize] {
+        let reverse = self
+            .reverse
+            .as_ref()
+            .expect("in-edges not built, call build_reverse first");
+        &reverse.sources[reverse.offsets[node]..reverse.offsets[node + 1]]
+    }
+
+    // (in-neighbor, weight) pairs, needs build_reverse like in_neighbors
+    #[inline]
+    pub fn weighted_in_neighbors(&self, node: usize) -> impl Iterator<Item = (usize, f64)> + '_ {
+        let reverse = self
+            .reverse
+            .as_ref()
+            .expect("in-edges not built, call build_reverse first");
+        let weights = reverse.weights.as_deref();
+        (reverse.offsets[node]..reverse.offsets[node + 1])
+            .map(move |i| (reverse.sources[i], weights.map_or(1.0, |w| w[i])))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_edges_keeps_order() {
+        let graph = Graph::from_edges(4, &[(2, 3), (0, 2), (0, 1), (2, 0)]);
+
+        assert_eq!(graph.num_nodes, 4);
+        assert_eq!(graph.num_edges(), 4);
+        assert_eq!(graph.neighbors(0), &[2, 1]);
+        assert_eq!(graph.neighbors(1), &[] as &[usize]);
+        assert_eq!(graph.neighbors(2), &[3, 0]);
+        assert_eq!(graph.out_degree(3), 0);
+    }
+
+    #[test]
+    fn test_build_parallel_keeps_order() {
+        let edges: Vec<(usize, usize, f64)> = (0..20000)
+            .map(|i| ((i * 7919) % 300, i % 1000, i as f64))
+            .collect();
+
+        let mut expected = vec![Vec::new(); 300];
+        for &(src, dst, w) in &edges {
+            expected[src].push((dst, w));
+        }
+
+        let graph = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap()
+            .install(|| Graph::from_weighted_edges(300, &edges));
+
+        for (u, list) in expected.iter().enumerate() {
+            assert_eq!(&graph.weighted_neighbors(u).collect::<Vec<_>>(), list);
+        }
+    }
+
+    #[test]
+    fn test_from_adjacency_matches_from_edges() {
+        let a = Graph::from_adjacency(vec![vec![1, 2], vec![], vec![0], vec![2]]);
+        let b = Graph::from_edges(4, &[(0, 1), (0, 2), (2, 0), (3, 2)]);
+
+        for u in 0..4 {
+            assert_eq!(a.neighbors(u), b.neighbors(u));
+        }
+    }
+
+    #[test]
+    fn test_build_reverse() {
+        let mut graph = Graph::from_adjacency(vec![vec![1, 2], vec![2], vec![0], vec![2]]);
+        assert!(!graph.has_reverse());
+
+        graph.build_reverse();
+
+        assert!(graph.has_reverse());
+        assert_eq!(graph.in_neighbors(0), &[2]);
+        assert_eq!(graph.in_neighbors(1), &[0]);
+        assert_eq!(graph.in_neighbors(2), &[0, 1, 3]);
+        assert_eq!(graph.in_neighbors(3), &[] as &[usize]);
+    }
+
+    #[test]
+    fn test_weighted_edges() {
+        let mut graph = Graph::from_weighted_edges(3, &[(1, 2, 0.5), (0, 1, 2.0), (0, 2, 1.5)]);
+        assert!(graph.is_weighted());
+
+        let out: Vec<_> = graph.weighted_neighbors(0).collect();
+        assert_eq!(out, vec![(1, 2.0), (2, 1.5)]);
+        assert_eq!(graph.out_weight(0), 3.5);
+        assert_eq!(graph.out_weight(2), 0.0);
+
+        graph.build_reverse();
+        let incoming: Vec<_> = graph.weighted_in_neighbors(2).collect();
+        assert_eq!(incoming, vec![(0, 1.5), (1, 0.5)]);
+    }
+
+    #[test]
+    fn test_from_file_weighted() {
+        let path = "test_graph_weighted.txt";
+        std::fs::write(path, "0 1 2.5\n1 2\n").unwrap();
+
+        let graph = Graph::from_file(path).unwrap();
+        assert!(graph.is_weighted());
+        assert_eq!(
+            graph.weighted_neighbors(0).collect::<Vec<_>>(),
+            vec![(1, 2.5)]
+        );
+        assert_eq!(
+            graph.weighted_neighbors(1).collect::<Vec<_>>(),
+            vec![(2, 1.0)]
+        );
+
+        std::fs::write(path, "0 1 -2\n").unwrap();
+        assert!(Graph::from_file(path).is_err());
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_transpose() {
+        let mut graph = Graph::from_adjacency(vec![vec![1, 2], vec![2], vec![]]);
+
+        graph.transpose();
+
+        assert_eq!(graph.neighbors(0), &[] as &[usize]);
+        assert_eq!(graph.neighbors(1), &[0]);
+        assert_eq!(graph.neighbors(2), &[0, 1]);
+        assert_eq!(graph.in_neighbors(0), &[1, 2]);
+    }
+
+    #[test]
+    fn test_symmetrize_keeps_existing_reverse_edges() {
+        // 0↔1 already exists in both directions, 1→2 does not
+        let mut graph =
+            Graph::from_weighted_edges(3, &[(0, 1, 1.0), (1, 0, 4.0), (1, 2, 2.0), (2, 2, 1.0)]);
+
+        graph.symmetrize();
+
+        assert_eq!(graph.num_edges(), 5);
+        assert_eq!(
+            graph.weighted_neighbors(0).collect::<Vec<_>>(),
+            vec![(1, 1.0)]
+        );
+        assert_eq!(
+            graph.weighted_neighbors(1).collect::<Vec<_>>(),
+            vec![(0, 4.0), (2, 2.0)]
+        );
+        assert_eq!(
+            graph.weighted_neighbors(2).collect::<Vec<_>>(),
+            vec![(2, 1.0), (1, 2.0)]
+        );
+        assert!(!graph.has_reverse());
+    }
+
+    #[test]
+    fn test_from_file_relabeled() {
+        let path = "test_graph_labels.txt";
+        std::fs::write(path, "web-01 db 2\n4000000000 web-01\ndb web-01\n").unwrap();
+
+        assert!(Graph::from_file(path).is_err());
+
+        let graph = Graph::from_file_relabeled(path).unwrap();
+        assert_eq!(graph.num_nodes, 3);
+        assert_eq!(
+            graph.labels().unwrap(),
+            &[
+                "web-01".to_string(),
+                "db".to_string(),
+                "4000000000".to_string()
+            ]
+        );
+        assert_eq!(graph.neighbors(0), &[1]);
+        assert_eq!(graph.neighbors(2), &[0]);
+        assert_eq!(graph.label(2), "4000000000");
+
+        let names = vec!["db".to_string(), "4000000000".to_string()];
+        assert_eq!(graph.resolve_nodes(&names), Ok(vec![1, 2]));
+        assert!(graph.resolve_nodes(&["cache".to_string()]).is_err());
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_resolve_numeric_nodes() {
+        let graph = Graph::from_adjacency(vec![vec![1], vec![]]);
+
+        assert_eq!(graph.label(1), "1");
+        assert_eq!(graph.resolve_nodes(&["1".to_string()]), Ok(vec![1]));
+        assert!(graph.resolve_nodes(&["2".to_string()]).is_err());
+        assert!(graph.resolve_nodes(&["x".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_clean() {
+        // 1 and 4 have no edges, 2 only a self-loop
+        let edges = [
+            (0, 3, 1.0),
+            (0, 5, 2.0),
+            (0, 3, 7.0),
+            (2, 2, 1.0),
+            (5, 0, 1.0),
+            (5, 5, 1.0),
+        ];
+        let all = CleanOptions {
+            sort: true,
+            dedup: true,
+            self_loops: true,
+            isolated: true,
+        };
+
+        let mut graph = Graph::from_weighted_edges(6, &edges);
+        let report = graph.clean(&CleanOptions {
+            dedup: true,
+            ..CleanOptions::default()
+        });
+        assert_eq!(report.duplicates, 1);
+        // order of first appearance, first weight kept
+        assert_eq!(
+            graph.weighted_neighbors(0).collect::<Vec<_>>(),
+            vec![(3, 1.0), (5, 2.0)]
+        );
+
+        let mut graph = Graph::from_weighted_edges(6, &edges);
+        let report = graph.clean(&all);
+        assert_eq!(
+            report,
+            CleanReport {
+                self_loops: 2,
+                duplicates: 1,
+                isolated: 3,
+            }
+        );
+        assert_eq!(graph.num_nodes, 3);
+        assert_eq!(graph.num_edges(), 3);
+        assert_eq!(graph.labels().unwrap(), &["0", "3", "5"]);
+        assert_eq!(graph.neighbors(0), &[1, 2]);
+        assert_eq!(graph.neighbors(2), &[0]);
+        assert_eq!(graph.resolve_nodes(&["5".to_string()]), Ok(vec![2]));
+
+        let mut graph = Graph::from_adjacency(vec![vec![2, 1, 2], vec![0]]);
+        graph.clean(&CleanOptions {
+            sort: true,
+            ..CleanOptions::default()
+        });
+        assert_eq!(graph.neighbors(0), &[1, 2, 2]);
+        assert!(graph.labels().is_none());
+    }
+
+    #[test]
+    fn test_from_file() {
+        let graph = Graph::from_file("test_directed.txt").unwrap();
+
+        assert_eq!(graph.num_nodes, 4);
+        assert_eq!(graph.num_edges(), 3);
+        assert_eq!(graph.neighbors(3), &[0]);
+        assert!(!graph.is_weighted());
     }
 }
@@ -1,20 +1,49 @@
+use crate::edge_list::EdgeList;
+use crate::formats::{GraphFormat, write_graph};
+use crate::graph::Graph;
 use rand::Rng;
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
-use std::fs::File;
-use std::io::BufWriter;
-use std::io::{Result, Write};
+use std::error::Error;
 
-pub fn generate_random(num_nodes: usize, num_edges: usize, output_path: &str) -> Result<()> {
-    let mut file = File::create(output_path)?;
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+// generators build the edges in memory, the graph is then written in the requested format
+// undirected writes every edge both ways (an edge that is already there both ways is not doubled)
+fn save(
+    num_nodes: usize,
+    edges: Vec<(usize, usize)>,
+    output_path: &str,
+    format: GraphFormat,
+    undirected: bool,
+) -> Result<()> {
+    let list = EdgeList {
+        edges,
+        weights: None,
+    };
+    let mut graph = Graph::from_edge_list(num_nodes, &list, None);
+    if undirected {
+        graph.symmetrize();
+    }
+    write_graph(&graph, output_path, format)
+}
+
+#[allow(dead_code)]
+pub fn generate_random(
+    num_nodes: usize,
+    num_edges: usize,
+    output_path: &str,
+    format: GraphFormat,
+    undirected: bool,
+) -> Result<()> {
+    let mut edges = Vec::with_capacity(num_edges);
     let mut rng = rand::rng();
 
     for _ in 0..num_edges {
         let src = rng.random_range(0..num_nodes);
         let dst = rng.random_range(0..num_nodes);
-        writeln!(file, "{} {}", src, dst)?;
+        edges.push((src, dst));
     }
 
-    Ok(())
+    save(num_nodes, edges, output_path, format, undirected)
 }
 
 use rayon::prelude::*;
@@ -23,36 +52,28 @@ pub fn generate_random_parallel(
     num_nodes: usize,
     num_edges: usize,
     output_path: &str,
+    format: GraphFormat,
+    undirected: bool,
 ) -> Result<()> {
     let chunk_size = 100_000;
-    let num_chunks = (num_edges + chunk_size - 1) / chunk_size;
+    let num_chunks = num_edges.div_ceil(chunk_size);
 
-    let chunks: Vec<String> = (0..num_chunks)
+    let chunks: Vec<Vec<(usize, usize)>> = (0..num_chunks)
         .into_par_iter()
-        .map_init(
-            || rand::rng(),
-            |rng: &mut rand::rngs::ThreadRng, chunk_idx| {
-                let start = chunk_idx * chunk_size;
-                let end = (start + chunk_size).min(num_edges);
-                let mut buf = String::with_capacity((end - start) * 20);
-                for _ in start..end {
+        .map_init(rand::rng, |rng: &mut rand::rngs::ThreadRng, chunk_idx| {
+            let start = chunk_idx * chunk_size;
+            let end = (start + chunk_size).min(num_edges);
+            (start..end)
+                .map(|_| {
                     let src = rng.random_range(0..num_nodes);
                     let dst = rng.random_range(0..num_nodes);
-                    use std::fmt::Write;
-                    writeln!(buf, "{} {}", src, dst).unwrap();
-                }
-                buf
-            },
-        )
+                    (src, dst)
+                })
+                .collect()
+        })
         .collect();
 
-    let file = File::create(output_path)?;
-    let mut writer = BufWriter::new(file);
-    for chunk in &chunks {
-        writer.write_all(chunk.as_bytes())?;
-    }
-
-    Ok(())
+    save(num_nodes, chunks.concat(), output_path, format, undirected)
 }
 
 pub fn generate_disconnected(
@@ -60,15 +81,14 @@ pub fn generate_disconnected(
     num_edges: usize,
     num_components: usize,
     output_path: &str,
+    format: GraphFormat,
+    undirected: bool,
 ) -> Result<()> {
     if num_components == 0 || num_components > num_nodes {
-        return Err(std::io::Error::new(
-            std::io::ErrorKind::InvalidInput,
-            "Invalid number of components",
-        ));
+        return Err("Invalid number of components".into());
     }
 
-    let mut file = File::create(output_path)?;
+    let mut edges = Vec::with_capacity(num_edges);
     let mut rng = rand::rng();
 
     let nodes_per_component = num_nodes / num_components;
@@ -91,57 +111,93 @@ pub fn generate_disconnected(
         for _ in 0..edges_for_this {
             let src = rng.random_range(start..end);
             let dst = rng.random_range(start..end);
-            writeln!(file, "{} {}", src, dst)?;
+            edges.push((src, dst));
         }
     }
-    Ok(())
-}
 
-pub fn generate_line(num_nodes: usize, output_path: &str) -> Result<()> {
-    let mut file = File::create(output_path)?;
+    save(num_nodes, edges, output_path, format, undirected)
+}
 
-    for i in 0..num_nodes - 1 {
-        writeln!(file, "{} {}", i, i + 1)?;
-    }
+pub fn generate_line(
+    num_nodes: usize,
+    output_path: &str,
+    format: GraphFormat,
+    undirected: bool,
+) -> Result<()> {
+    let edges = (0..num_nodes - 1).map(|i| (i, i + 1)).collect();
 
-    Ok(())
+    save(num_nodes, edges, output_path, format, undirected)
 }
 
-pub fn generate_star(num_nodes: usize, output_path: &str) -> Result<()> {
-    let mut file = File::create(output_path)?;
-
-    for i in 1..num_nodes {
-        writeln!(file, "0 {}", i)?;
-    }
+pub fn generate_star(
+    num_nodes: usize,
+    output_path: &str,
+    format: GraphFormat,
+    undirected: bool,
+) -> Result<()> {
+    let edges = (1..num_nodes).map(|i| (0, i)).collect();
 
-    Ok(())
+    save(num_nodes, edges, output_path, format, undirected)
 }
 
 // every node with every other, for testing dense graphs
-pub fn generate_complete(num_nodes: usize, output_path: &str) -> Result<()> {
-    let mut file = File::create(output_path)?;
-
-    // let num_edges = num_nodes * (num_nodes - 1); //number of edges
+pub fn generate_complete(
+    num_nodes: usize,
+    output_path: &str,
+    format: GraphFormat,
+    undirected: bool,
+) -> Result<()> {
+    let mut edges = Vec::with_capacity(num_nodes * num_nodes.saturating_sub(1));
 
     for i in 0..num_nodes {
         for j in 0..num_nodes {
             if i != j {
-                writeln!(file, "{} {}", i, j)?;
+                edges.push((i, j));
             }
         }
     }
 
-    Ok(())
+    save(num_nodes, edges, output_path, format, undirected)
+}
+
+pub fn generate_cycle(
+    num_nodes: usize,
+    output_path: &str,
+    format: GraphFormat,
+    undirected: bool,
+) -> Result<()> {
+    let mut edges: Vec<(usize, usize)> = (0..num_nodes - 1).map(|i| (i, i + 1)).collect();
+
+    edges.push((num_nodes - 1, 0));
+
+    save(num_nodes, edges, output_path, format, undirected)
 }
 
-pub fn generate_cycle(num_nodes: usize, output_path: &str) -> Result<()> {
-    let mut file = File::create(output_path)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    for i in 0..num_nodes - 1 {
-        writeln!(file, "{} {}", i, i + 1)?;
-    }
+    #[test]
+    fn test_undirected_generators() {
+        let path = "test_generator_undirected.txt";
 
-    writeln!(file, "{} 0", num_nodes - 1)?;
+        generate_line(3, path, GraphFormat::EdgeList, true).unwrap();
+        let graph = Graph::from_file(path).unwrap();
+        assert_eq!(graph.num_edges(), 4);
+        assert_eq!(graph.neighbors(1), &[2, 0]);
 
-    Ok(())
+        // already symmetric, nothing is added
+        generate_complete(4, path, GraphFormat::EdgeList, true).unwrap();
+        assert_eq!(Graph::from_file(path).unwrap().num_edges(), 12);
+
+        generate_random_parallel(50, 200, path, GraphFormat::EdgeList, true).unwrap();
+        let graph = Graph::from_file(path).unwrap();
+        for u in 0..graph.num_nodes {
+            for &v in graph.neighbors(u) {
+                assert!(graph.neighbors(v).contains(&u));
+            }
+        }
+
+        std::fs::remove_file(path).ok();
+    }
 }
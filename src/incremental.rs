@@ -0,0 +1,253 @@
+use crate::graph::Graph;
+use crate::wcc::Connectivity;
+use std::collections::HashMap;
+use std::io::{BufRead, Result, Write};
+use std::time::Instant;
+
+// stream node ids to indices of the Connectivity, like --relabel when the graph has labels
+pub struct NodeIds {
+    index: Option<HashMap<String, usize>>,
+    labels: Vec<String>,
+}
+
+impl NodeIds {
+    pub fn new(graph: &Graph) -> Self {
+        match graph.labels() {
+            Some(labels) => NodeIds {
+                index: Some(
+                    labels
+                        .iter()
+                        .enumerate()
+                        .map(|(i, label)| (label.clone(), i))
+                        .collect(),
+                ),
+                labels: labels.to_vec(),
+            },
+            None => NodeIds {
+                index: None,
+                labels: Vec::new(),
+            },
+        }
+    }
+
+    // node of an edge end, a node that was not seen yet gets the next free index;
+    // numeric ids have to be below `limit`, every id below a typo like 99999999999 would get a node
+    fn insert(&mut self, token: &str, limit: usize) -> Option<usize> {
+        match &mut self.index {
+            Some(index) => {
+                let next = self.labels.len();
+                let id = *index.entry(token.to_string()).or_insert(next);
+                if id == next {
+                    self.labels.push(token.to_string());
+                }
+                Some(id)
+            }
+            None => token.parse().ok().filter(|&id| id < limit),
+        }
+    }
+
+    fn get(&self, token: &str, num_nodes: usize) -> Option<usize> {
+        match &self.index {
+            Some(index) => index.get(token).copied(),
+            None => token.parse().ok().filter(|&id| id < num_nodes),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct StreamSummary {
+    pub batches: usize,
+    pub edges: usize,
+    pub queries: usize,
+    pub bad_lines: usize,
+}
+
+// "u v" lines are new edges, they are collected and applied as one batch before the next
+// query, every batch_size edges and at the end of the stream; queries are
+// "connected u v", "size u" and "components", answers and batch reports go to out
+pub fn process_stream(
+    connectivity: &mut Connectivity,
+    nodes: &mut NodeIds,
+    input: impl BufRead,
+    out: &mut dyn Write,
+    batch_size: usize,
+) -> Result<StreamSummary> {
+    let mut summary = StreamSummary::default();
+    let mut batch = Vec::new();
+
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.is_empty() || tokens[0].starts_with("//") || tokens[0].starts_with('#') {
+            continue;
+        }
+
+        let is_query = matches!(tokens[0], "connected" | "size" | "components");
+        if !is_query {
+            // a batch can add at most batch_size numeric nodes
+            let limit = connectivity.num_nodes().saturating_add(batch_size);
+            let edge = match tokens[..] {
+                [u, v] => nodes
+                    .insert(u, limit)
+                    .zip(nodes.insert(v, limit))
+                    .ok_or_else(|| format!("node ids must be numbers below {}", limit)),
+                _ => Err("not an edge or a query".to_string()),
+            };
+            match edge {
+                Ok(edge) => batch.push(edge),
+                Err(problem) => skip_line(&mut summary, i + 1, &line, &problem),
+            }
+            if batch.len() >= batch_size {
+                apply_batch(connectivity, &mut batch, &mut summary, out)?;
+            }
+            continue;
+        }
+
+        if !batch.is_empty() {
+            apply_batch(connectivity, &mut batch, &mut summary, out)?;
+        }
+        let n = connectivity.num_nodes();
+        let node = |token: &str| nodes.get(token, n);
+        let answer = match tokens[..] {
+            ["connected", u, v] => node(u).zip(node(v)).map(|(a, b)| {
+                let answer = if connectivity.connected(a, b) {
+                    "yes"
+                } else {
+                    "no"
+                };
+                format!("connected {} {}: {}", u, v, answer)
+            }),
+            ["size", u] => {
+                node(u).map(|a| format!("size {}: {}", u, connectivity.component_size(a)))
+            }
+            ["components"] => Some(format!("components: {}", connectivity.num_components())),
+            _ => None,
+        };
+        match answer {
+            Some(answer) => {
+                writeln!(out, "{}", answer)?;
+                summary.queries += 1;
+            }
+            None => skip_line(
+                &mut summary,
+                i + 1,
+                &line,
+                "unknown node or wrong arguments",
+            ),
+        }
+    }
+
+    if !batch.is_empty() {
+        apply_batch(connectivity, &mut batch, &mut summary, out)?;
+    }
+    out.flush()?;
+    Ok(summary)
+}
+
+fn apply_batch(
+    connectivity: &mut Connectivity,
+    batch: &mut Vec<(usize, usize)>,
+    summary: &mut StreamSummary,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let start = Instant::now();
+    connectivity.add_edges(batch);
+    summary.batches += 1;
+    summary.edges += batch.len();
+    writeln!(
+        out,
+        "batch {}: {} edges applied in {:?}, {} components",
+        summary.batches,
+        batch.len(),
+        start.elapsed(),
+        connectivity.num_components()
+    )?;
+    batch.clear();
+    Ok(())
+}
+
+// a broken line shouldn't stop a long running stream
+fn skip_line(summary: &mut StreamSummary, line: usize, text: &str, problem: &str) {
+    eprintln!("Skipped line {}: {} '{}'", line, problem, text);
+    summary.bad_lines += 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(graph: &Graph, input: &str, batch_size: usize) -> (Vec<String>, StreamSummary) {
+        let mut connectivity = Connectivity::from_graph(graph, 2);
+        let mut nodes = NodeIds::new(graph);
+        let mut out = Vec::new();
+        let summary = process_stream(
+            &mut connectivity,
+            &mut nodes,
+            input.as_bytes(),
+            &mut out,
+            batch_size,
+        )
+        .unwrap();
+        let lines = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .filter(|line| !line.starts_with("batch"))
+            .map(str::to_string)
+            .collect();
+        (lines, summary)
+    }
+
+    #[test]
+    fn test_process_stream() {
+        let graph = Graph::from_edges(4, &[(0, 1)]);
+        let input = "connected 0 2\n1 2\n2 3\n// comment\nconnected 0 2\nsize 3\n\
+                     5 4\nbroken\nsize 9\ncomponents\nsize 5\n";
+        let (lines, summary) = run(&graph, input, 1000);
+
+        assert_eq!(
+            lines,
+            vec![
+                "connected 0 2: no",
+                "connected 0 2: yes",
+                "size 3: 4",
+                "components: 2",
+                "size 5: 2"
+            ]
+        );
+        // "size 9" asks for a node that doesn't exist
+        assert_eq!(
+            summary,
+            StreamSummary {
+                batches: 2,
+                edges: 3,
+                queries: 5,
+                bad_lines: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_process_stream_huge_ids() {
+        let graph = Graph::from_edges(4, &[(0, 1)]);
+        let input = "0 99999999999999\n18446744073709551615 0\n3 13\n3 14\nsize 3\ncomponents\n";
+        let (lines, summary) = run(&graph, input, 10);
+
+        // 13 is the last id a batch of 10 may add to 4 nodes
+        assert_eq!(lines, vec!["size 3: 2", "components: 12"]);
+        assert_eq!(summary.bad_lines, 3);
+        assert_eq!(summary.edges, 1);
+    }
+
+    #[test]
+    fn test_process_stream_labels() {
+        let path = "test_incremental_labels.txt";
+        std::fs::write(path, "a b\n").unwrap();
+        let graph = Graph::from_file_relabeled(path).unwrap();
+        std::fs::remove_file(path).ok();
+
+        let (lines, summary) = run(&graph, "b c\nx y\nsize a\nconnected c x\nsize y\n", 1);
+
+        assert_eq!(lines, vec!["size a: 3", "connected c x: no", "size y: 2"]);
+        assert_eq!(summary.batches, 2);
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+// what is wrong with a single line of an input file
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Problem {
+    Columns,
+    NodeId,
+    OutOfRange,
+    Weight,
+    LineType,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Problem::Columns => "wrong number of columns",
+            Problem::NodeId => "node id is not a number",
+            Problem::OutOfRange => "node id out of range",
+            Problem::Weight => "invalid weight",
+            Problem::LineType => "unknown line type",
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LineError {
+    pub line: usize,
+    pub problem: Problem,
+    pub text: String,
+}
+
+// lines listed in the --lenient summary, strict errors list all of them
+pub const SUMMARY_LINES: usize = 20;
+
+// bad lines of one file: how many of every kind, and every one of them in file order
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Problems {
+    pub count: usize,
+    pub by_kind: BTreeMap<Problem, usize>,
+    pub lines: Vec<LineError>,
+}
+
+impl Problems {
+    pub fn push(&mut self, line: usize, problem: Problem, text: &str) {
+        self.count += 1;
+        *self.by_kind.entry(problem).or_insert(0) += 1;
+        self.lines.push(LineError {
+            line,
+            problem,
+            text: text.trim().to_string(),
+        });
+    }
+
+    // problems of the next chunk of the same file, its lines start after `line_offset`
+    pub fn append(&mut self, other: Problems, line_offset: usize) {
+        self.count += other.count;
+        for (kind, n) in other.by_kind {
+            *self.by_kind.entry(kind).or_insert(0) += n;
+        }
+        self.lines.extend(other.lines.into_iter().map(|mut e| {
+            e.line += line_offset;
+            e
+        }));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    // strict loading: a single bad line fails the whole file
+    pub fn check(self, path: &str) -> Result<(), LoadError> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        Err(LoadError::Lines {
+            path: path.to_string(),
+            problems: self,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    // strict mode: every bad line of the file
+    Lines {
+        path: String,
+        problems: Problems,
+    },
+    // the file can't be read at all (bad header, wrong version, corrupt, ...)
+    Format {
+        path: String,
+        line: Option<usize>,
+        message: String,
+    },
+}
+
+impl LoadError {
+    pub fn io(path: &str, source: std::io::Error) -> Self {
+        LoadError::Io {
+            path: path.to_string(),
+            source,
+        }
+    }
+
+    pub fn format(path: &str, line: Option<usize>, message: impl Into<String>) -> Self {
+        LoadError::Format {
+            path: path.to_string(),
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io { path, source } => write!(f, "{}: {}", path, source),
+            LoadError::Format {
+                path,
+                line: Some(line),
+                message,
+            } => write!(f, "{}:{}: {}", path, line, message),
+            LoadError::Format {
+                path,
+                line: None,
+                message,
+            } => write!(f, "{}: {}", path, message),
+            LoadError::Lines { path, problems } => {
+                write!(
+                    f,
+                    "{} has {} bad lines (use --lenient to skip them)",
+                    path, problems.count
+                )?;
+                for e in &problems.lines {
+                    write!(f, "\n   {}:{}: {} '{}'", path, e.line, e.problem, e.text)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_problems_append() {
+        let mut first = Problems::default();
+        first.push(3, Problem::Columns, "1 2 3 4");
+
+        let mut second = Problems::default();
+        for line in 1..=30 {
+            second.push(line, Problem::NodeId, "x 1");
+        }
+
+        first.append(second, 100);
+
+        assert_eq!(first.count, 31);
+        assert_eq!(first.by_kind[&Problem::NodeId], 30);
+        assert_eq!(first.lines.len(), 31);
+        assert_eq!(first.lines[0].line, 3);
+        assert_eq!(first.lines[1].line, 101);
+        assert_eq!(first.lines[30].line, 130);
+
+        let error = LoadError::Lines {
+            path: "g.txt".to_string(),
+            problems: first,
+        };
+        let text = error.to_string();
+        // strict mode lists every bad line, not only the first few
+        assert_eq!(text.lines().count(), 32);
+        assert!(text.contains("g.txt:3: wrong number of columns '1 2 3 4'"));
+        assert!(text.contains("g.txt:130: node id is not a number 'x 1'"));
+        assert!(!text.contains("more"));
+    }
+}
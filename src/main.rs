@@ -2,114 +2,335 @@
 //E241/2025
 
 mod bfs;
+mod binary;
 mod cli;
+mod critical;
+mod edge_list;
+mod formats;
 mod graph;
 mod graph_generator;
+mod incremental;
+mod load_error;
+mod node_list;
+mod output;
 mod pagerank;
+mod resilience;
+mod scc;
+mod sssp;
+mod stats;
 mod wcc;
 
+use bfs::bfs_hybrid;
 use bfs::bfs_parallel;
 use bfs::bfs_sequential;
+use bfs::{
+    NO_SOURCE, bfs_multi_parallel, bfs_multi_sequential, bfs_tree_parallel, bfs_tree_sequential,
+    reconstruct_path,
+};
 use clap::Parser;
-use cli::{Cli, Commands};
-use graph::Graph;
-use pagerank::{pagerank_parallel, pagerank_sequential};
-use std::fs::File;
-use std::io::Write;
-use wcc::wcc_parallel;
-use wcc::wcc_sequential; //koja je razlika izmedju ovog i use crate::wcc...?
+use cli::{CleanArgs, Cli, Commands, LoadArgs};
+use critical::{Critical, critical_parallel, critical_sequential};
+use formats::{GraphFormat, LoadOptions, detect_input, read_graph, write_graph};
+use graph::{CleanOptions, Graph};
+use incremental::{NodeIds, process_stream};
+use load_error::SUMMARY_LINES;
+use node_list::{parse_node_list, read_node_list};
+use output::{Compression, Format, Nodes, OutputOptions, save_columns};
+use pagerank::{
+    Dangling, pagerank_parallel, pagerank_pull, pagerank_sequential, personalization_vector,
+};
+use resilience::{RemovalOrder, removal_order, resilience, robustness, write_steps};
+use scc::{scc_parallel, scc_sequential};
+use sssp::{default_delta, delta_stepping, dijkstra};
+use stats::{graph_stats, save_stats, write_stats_text};
+use std::io::{BufRead, Write};
+use wcc::wcc_sequential;
+use wcc::{
+    Connectivity, Labeling, canonical_labels, component_summary, wcc_afforest,
+    wcc_label_propagation, wcc_parallel, wcc_shiloach_vishkin,
+}; //koja je razlika izmedju ovog i use crate::wcc...?
 
 fn main() {
     let cli = Cli::parse();
 
     match cli.command {
         Commands::Bfs {
-            input,
+            load,
             source,
+            sources,
+            sources_file,
             mode,
             threads,
+            parents,
+            direction,
             out,
+            format,
+            compress,
         } => {
-            println!("Loading graph from: {}", input);
-            let graph = match Graph::from_file(&input) {
-                Ok(g) => {
-                    println!("Graph loaded: {} nodes", g.num_nodes);
-                    g
+            let options = output_options(&format, compress.as_deref(), &out);
+            if parents && mode == "hybrid" {
+                eprintln!("Error: --parents works only with 'seq' and 'par' mode");
+                std::process::exit(1);
+            }
+
+            let mut graph = load_graph(&load);
+
+            match direction.as_str() {
+                "forward" => {}
+                "backward" | "both" if load.undirected => {
+                    println!(
+                        "Edges are already undirected, --direction {} changes nothing",
+                        direction
+                    );
+                }
+                "backward" => {
+                    println!("Following in-edges (upstream)");
+                    graph.transpose();
                 }
+                "both" => {
+                    println!("Ignoring edge direction");
+                    graph.symmetrize();
+                }
+                _ => {
+                    eprintln!("Error: direction must be 'forward', 'backward' or 'both'");
+                    std::process::exit(1);
+                }
+            }
+
+            let multi = load_node_lists(&graph, sources.as_deref(), sources_file.as_deref());
+            let source = match (source, multi) {
+                (Some(source), None) => resolve_node(&graph, &source),
+                (None, None) => {
+                    eprintln!("Error: BFS needs --source, --sources or --sources-file");
+                    std::process::exit(1);
+                }
+                (source, Some(list)) => {
+                    let mut nodes: Vec<usize> = list.iter().map(|&(node, _)| node).collect();
+                    nodes.extend(source.map(|s| resolve_node(&graph, &s)));
+                    run_multi_bfs(&graph, &nodes, &mode, threads, parents, &out, &options);
+                    return;
+                }
+            };
+
+            let (result, parent) = match mode.as_str() {
+                "seq" => {
+                    println!("Running seq BFS from node {}...", graph.label(source));
+                    let start = std::time::Instant::now();
+                    let res = if parents {
+                        let (dist, parent) = bfs_tree_sequential(&graph, source);
+                        (dist, Some(parent))
+                    } else {
+                        (bfs_sequential(&graph, source), None)
+                    };
+                    let duration = start.elapsed();
+                    println!("BFS finished in: {:?}", duration);
+                    res
+                }
+                "par" => {
+                    let threads = threads.unwrap_or(8);
+                    println!(
+                        "Running parallel BFS from node {} with {} threads...",
+                        graph.label(source),
+                        threads
+                    );
+                    let start = std::time::Instant::now();
+                    let res = if parents {
+                        let (dist, parent) = bfs_tree_parallel(&graph, source, threads);
+                        (dist, Some(parent))
+                    } else {
+                        (bfs_parallel(&graph, source, threads), None)
+                    };
+                    let duration = start.elapsed();
+                    println!("BFS finished in: {:?}", duration);
+                    res
+                }
+                "hybrid" => {
+                    let threads = threads.unwrap_or(8);
+                    let start = std::time::Instant::now();
+                    graph.build_reverse();
+                    println!("In-edges built in: {:?}", start.elapsed());
+
+                    println!(
+                        "Running direction-optimizing BFS from node {} with {} threads...",
+                        graph.label(source),
+                        threads
+                    );
+                    let start = std::time::Instant::now();
+                    let res = bfs_hybrid(&graph, source, threads);
+                    let duration = start.elapsed();
+                    println!("BFS finished in: {:?}", duration);
+                    (res, None)
+                }
+                _ => {
+                    eprintln!("Error: mode has to be 'seq', 'par' or 'hybrid'");
+                    std::process::exit(1);
+                }
+            };
+
+            match save_bfs_result(&graph, &result, parent.as_deref(), &out, &options) {
+                Ok(_) => println!("Result saved in: {}", out),
                 Err(e) => {
-                    eprintln!("Error while loading graph: {}", e);
+                    eprintln!("Error while saving results: {}", e);
+                    std::process::exit(1);
+                }
+            }
+
+            print_bfs_stats(&graph, &result, source);
+        }
+
+        Commands::Path {
+            load,
+            source,
+            target,
+            mode,
+            threads,
+        } => {
+            let graph = load_graph(&load);
+
+            let source = resolve_node(&graph, &source);
+            let target = resolve_node(&graph, &target);
+
+            let parent = match mode.as_str() {
+                "seq" => {
+                    println!("Running seq BFS from node {}...", graph.label(source));
+                    let start = std::time::Instant::now();
+                    let (_, parent) = bfs_tree_sequential(&graph, source);
+                    println!("BFS finished in: {:?}", start.elapsed());
+                    parent
+                }
+                "par" => {
+                    let threads = threads.unwrap_or(8);
+                    println!(
+                        "Running parallel BFS from node {} with {} threads...",
+                        graph.label(source),
+                        threads
+                    );
+                    let start = std::time::Instant::now();
+                    let (_, parent) = bfs_tree_parallel(&graph, source, threads);
+                    println!("BFS finished in: {:?}", start.elapsed());
+                    parent
+                }
+                _ => {
+                    eprintln!("Error: mode has to be either 'seq' or 'par'");
                     std::process::exit(1);
                 }
             };
 
-            if source >= graph.num_nodes {
-                eprintln!(
-                    "Error: source node {} doenst exist (max is {})",
-                    source,
-                    graph.num_nodes - 1
-                );
+            match reconstruct_path(&parent, source, target) {
+                Some(path) => {
+                    let hops: Vec<String> = path.iter().map(|&n| graph.label(n)).collect();
+                    println!(
+                        "\nPath from {} to {} ({} hops):",
+                        graph.label(source),
+                        graph.label(target),
+                        path.len() - 1
+                    );
+                    println!("   {}", hops.join(" -> "));
+                }
+                None => {
+                    println!(
+                        "\nNode {} is not reachable from node {}",
+                        graph.label(target),
+                        graph.label(source)
+                    );
+                }
+            }
+        }
+
+        Commands::Sssp {
+            load,
+            source,
+            mode,
+            threads,
+            delta,
+            out,
+            format,
+            compress,
+        } => {
+            let options = output_options(&format, compress.as_deref(), &out);
+            if delta.is_some_and(|delta| delta <= 0.0 || !delta.is_finite()) {
+                eprintln!("Error: delta must be a positive number");
                 std::process::exit(1);
             }
 
+            let graph = load_graph(&load);
+            let source = resolve_node(&graph, &source);
+
             let result = match mode.as_str() {
                 "seq" => {
-                    println!("Running seq BFS from node {}...", source);
+                    println!("Running Dijkstra from node {}...", graph.label(source));
                     let start = std::time::Instant::now();
-                    let res = bfs_sequential(&graph, source);
+                    let res = dijkstra(&graph, source);
                     let duration = start.elapsed();
-                    println!("BFS finished in: {:?}", duration);
+                    println!("SSSP finished in: {:?}", duration);
                     res
                 }
                 "par" => {
+                    let threads = threads.unwrap_or(8);
+                    let delta = delta.unwrap_or_else(|| default_delta(&graph));
                     println!(
-                        "Running parallel BFS from node {} with {} threads...",
-                        source,
-                        threads.unwrap_or(8)
+                        "Running delta-stepping from node {} with delta {} and {} threads...",
+                        graph.label(source),
+                        delta,
+                        threads
                     );
-                    let res;
                     let start = std::time::Instant::now();
-                    match threads {
-                        Some(threads) => res = bfs_parallel(&graph, source, threads),
-                        None => res = bfs_parallel(&graph, source, 8),
-                    }
+                    let res = delta_stepping(&graph, source, delta, threads);
                     let duration = start.elapsed();
-                    println!("BFS finished in: {:?}", duration);
+                    println!("SSSP finished in: {:?}", duration);
                     res
                 }
                 _ => {
-                    eprintln!("Error: mode has to be either 'seq' or 'par'");
+                    eprintln!("Error: mode must be 'seq' or 'par'");
                     std::process::exit(1);
                 }
             };
 
-            // match save_bfs_result(&result, &out) {
-            //     Ok(_) => println!("Result saved in: {}", out),
-            //     Err(e) => {
-            //         eprintln!("Error while saving results: {}", e);
-            //         std::process::exit(1);
-            //     }
-            // }
+            match save_sssp_result(&graph, &result, &out, &options) {
+                Ok(_) => println!("Result saved to: {}", out),
+                Err(e) => {
+                    eprintln!("Error saving result: {}", e);
+                    std::process::exit(1);
+                }
+            }
 
-            print_bfs_stats(&result, source);
+            print_sssp_stats(&graph, &result, source);
         }
 
         Commands::Wcc {
-            input,
+            load,
             mode,
             threads,
             out,
+            format,
+            compress,
+            algorithm,
+            labels,
+            top,
+            members,
         } => {
-            println!("Loading graph from: {}", input);
-            let graph = match Graph::from_file(&input) {
-                Ok(g) => {
-                    println!("Graph loaded: {} nodes", g.num_nodes);
-                    g
-                }
-                Err(e) => {
-                    eprintln!("Error loading graph: {}", e);
+            let options = output_options(&format, compress.as_deref(), &out);
+            let Some(labeling) = Labeling::parse(&labels) else {
+                eprintln!("Error: labels must be 'min', 'dense' or 'root'");
+                std::process::exit(1);
+            };
+
+            let wcc_par = match algorithm.as_str() {
+                "union-find" => wcc_parallel,
+                "afforest" => wcc_afforest,
+                "sv" => wcc_shiloach_vishkin,
+                "lp" => wcc_label_propagation,
+                _ => {
+                    eprintln!("Error: algorithm must be 'union-find', 'afforest', 'sv' or 'lp'");
                     std::process::exit(1);
                 }
             };
+            if mode == "seq" && algorithm != "union-find" {
+                eprintln!("Error: --algorithm {} needs --mode par", algorithm);
+                std::process::exit(1);
+            }
+
+            let mut graph = load_graph(&load);
 
             let result = match mode.as_str() {
                 "seq" => {
@@ -122,9 +343,18 @@ fn main() {
                 }
                 "par" => {
                     let threads = threads.unwrap_or(8);
-                    println!("Running parallel WCC with {} threads...", threads);
+                    if algorithm == "afforest" {
+                        let start = std::time::Instant::now();
+                        graph.build_reverse();
+                        println!("In-edges built in: {:?}", start.elapsed());
+                    }
+
+                    println!(
+                        "Running parallel WCC ({}) with {} threads...",
+                        algorithm, threads
+                    );
                     let start = std::time::Instant::now();
-                    let res = wcc_parallel(&graph, threads);
+                    let res = wcc_par(&graph, threads);
                     let duration = start.elapsed();
                     println!("WCC finished in: {:?}", duration);
                     res
@@ -135,43 +365,249 @@ fn main() {
                 }
             };
 
-            // match save_wcc_result(&result, &out) {
-            //     Ok(_) => println!("Result saved to: {}", out),
-            //     Err(e) => {
-            //         eprintln!("Error saving result: {}", e);
-            //         std::process::exit(1);
-            //     }
-            // }
+            let result = canonical_labels(&result, labeling);
+            match save_wcc_result(&graph, &result, labeling, &out, &options) {
+                Ok(_) => println!("Result saved to: {}", out),
+                Err(e) => {
+                    eprintln!("Error saving result: {}", e);
+                    std::process::exit(1);
+                }
+            }
+
+            print_wcc_stats(&graph, &result, labeling, top, members.as_deref());
+        }
+
+        Commands::Incremental {
+            load,
+            threads,
+            updates,
+            batch_size,
+        } => {
+            let input: Box<dyn BufRead> = match updates.as_deref() {
+                Some(path) if path != "-" => match std::fs::File::open(path) {
+                    Ok(file) => Box::new(std::io::BufReader::new(file)),
+                    Err(e) => {
+                        eprintln!("Error: cannot open {}: {}", path, e);
+                        std::process::exit(1);
+                    }
+                },
+                _ => Box::new(std::io::stdin().lock()),
+            };
+
+            let graph = load_graph(&load);
+            let threads = threads.unwrap_or(8);
+
+            let start = std::time::Instant::now();
+            let mut connectivity = Connectivity::from_graph(&graph, threads);
+            println!("Initial WCC finished in: {:?}", start.elapsed());
+            println!("Components: {}", connectivity.num_components());
+
+            let mut nodes = NodeIds::new(&graph);
+            drop(graph);
+            let start = std::time::Instant::now();
+            let summary = match process_stream(
+                &mut connectivity,
+                &mut nodes,
+                input,
+                &mut std::io::stdout().lock(),
+                batch_size.max(1),
+            ) {
+                Ok(summary) => summary,
+                Err(e) => {
+                    eprintln!("Error reading updates: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            println!("Updates finished in: {:?}", start.elapsed());
+            println!(
+                "{} edges in {} batches, {} queries, {} skipped lines",
+                summary.edges, summary.batches, summary.queries, summary.bad_lines
+            );
+            println!("Components: {}", connectivity.num_components());
+        }
+
+        Commands::Scc {
+            load,
+            mode,
+            threads,
+            out,
+            format,
+            compress,
+        } => {
+            let options = output_options(&format, compress.as_deref(), &out);
+
+            let mut graph = load_graph(&load);
+
+            let result = match mode.as_str() {
+                "seq" => {
+                    println!("Running sequential SCC...");
+                    let start = std::time::Instant::now();
+                    let res = scc_sequential(&graph);
+                    let duration = start.elapsed();
+                    println!("SCC finished in: {:?}", duration);
+                    res
+                }
+                "par" => {
+                    let threads = threads.unwrap_or(8);
+                    let start = std::time::Instant::now();
+                    graph.build_reverse();
+                    println!("In-edges built in: {:?}", start.elapsed());
+
+                    println!("Running parallel SCC with {} threads...", threads);
+                    let start = std::time::Instant::now();
+                    let res = scc_parallel(&graph, threads);
+                    let duration = start.elapsed();
+                    println!("SCC finished in: {:?}", duration);
+                    res
+                }
+                _ => {
+                    eprintln!("Error: mode must be 'seq' or 'par'");
+                    std::process::exit(1);
+                }
+            };
+
+            match save_scc_result(&graph, &result, &out, &options) {
+                Ok(_) => println!("Result saved to: {}", out),
+                Err(e) => {
+                    eprintln!("Error saving result: {}", e);
+                    std::process::exit(1);
+                }
+            }
 
-            print_wcc_stats(&result);
+            print_scc_stats(&result);
+        }
+
+        Commands::Critical {
+            load,
+            mode,
+            threads,
+            out,
+            format,
+            compress,
+            bridges,
+            blocks,
+        } => {
+            let options = output_options(&format, compress.as_deref(), &out);
+
+            let mut graph = load_graph(&load);
+
+            let start = std::time::Instant::now();
+            graph.symmetrize();
+            graph.clean(&CleanOptions {
+                dedup: true,
+                self_loops: true,
+                ..CleanOptions::default()
+            });
+            println!(
+                "Undirected graph built in: {:?} ({} edges)",
+                start.elapsed(),
+                graph.num_edges()
+            );
+
+            let result = match mode.as_str() {
+                "seq" => {
+                    println!("Running sequential biconnectivity...");
+                    let start = std::time::Instant::now();
+                    let res = critical_sequential(&graph);
+                    println!("Critical finished in: {:?}", start.elapsed());
+                    res
+                }
+                "par" => {
+                    let threads = threads.unwrap_or(8);
+                    println!(
+                        "Running parallel biconnectivity with {} threads...",
+                        threads
+                    );
+                    let start = std::time::Instant::now();
+                    let res = critical_parallel(&graph, threads);
+                    println!("Critical finished in: {:?}", start.elapsed());
+                    res
+                }
+                _ => {
+                    eprintln!("Error: mode must be 'seq' or 'par'");
+                    std::process::exit(1);
+                }
+            };
+
+            let cut_off: &[usize] = &result.cut_off;
+            match save_columns(&out, &options, graph.labels(), &[("cut_off", &cut_off)]) {
+                Ok(_) => println!("Result saved to: {}", out),
+                Err(e) => {
+                    eprintln!("Error saving result: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            if let Some(path) = bridges.as_deref() {
+                save_lines(path, "Bridges", |w| {
+                    for &(u, v) in &result.bridges {
+                        writeln!(w, "{} {}", graph.label(u), graph.label(v))?;
+                    }
+                    Ok(())
+                });
+            }
+            if let Some(path) = blocks.as_deref() {
+                save_lines(path, "Blocks", |w| {
+                    for (i, block) in result.blocks.iter().enumerate() {
+                        for &u in block {
+                            writeln!(w, "{} {}", i, graph.label(u))?;
+                        }
+                    }
+                    Ok(())
+                });
+            }
+
+            print_critical_stats(&graph, &result);
         }
 
         Commands::Pagerank {
-            input,
+            load,
             mode,
             threads,
             out,
+            format,
+            compress,
             alpha,
             iters,
             eps,
+            dangling,
+            seeds,
+            seeds_file,
         } => {
-            println!("Loading graph from: {}", input);
-            let graph = match Graph::from_file(&input) {
-                Ok(g) => {
-                    println!("Graph loaded: {} nodes", g.num_nodes);
-                    g
-                }
-                Err(e) => {
-                    eprintln!("Error loading graph: {}", e);
+            let options = output_options(&format, compress.as_deref(), &out);
+            let dangling = match Dangling::parse(&dangling) {
+                Some(d) => d,
+                None => {
+                    eprintln!(
+                        "Error: dangling must be 'uniform', 'personalization', 'self-loop' or 'ignore'"
+                    );
                     std::process::exit(1);
                 }
             };
 
+            let mut graph = load_graph(&load);
+
+            let personalization =
+                match load_node_lists(&graph, seeds.as_deref(), seeds_file.as_deref()) {
+                    Some(seeds) => match personalization_vector(graph.num_nodes, &seeds) {
+                        Ok(p) => {
+                            println!("Personalized PageRank from {} seed nodes", seeds.len());
+                            Some(p)
+                        }
+                        Err(e) => {
+                            eprintln!("Error: {}", e);
+                            std::process::exit(1);
+                        }
+                    },
+                    None => None,
+                };
+            let personalization = personalization.as_deref();
+
             let result = match mode.as_str() {
                 "seq" => {
                     println!("Running sequential PageRank...");
                     let start = std::time::Instant::now();
-                    let res = pagerank_sequential(&graph, alpha, iters, eps);
+                    let res =
+                        pagerank_sequential(&graph, alpha, iters, eps, dangling, personalization);
                     let duration = start.elapsed();
                     println!("PageRank finished in: {:?}", duration);
                     res
@@ -180,26 +616,196 @@ fn main() {
                     let threads = threads.unwrap_or(8);
                     println!("Running parallel PageRank with {} threads...", threads);
                     let start = std::time::Instant::now();
-                    let res = pagerank_parallel(&graph, alpha, iters, eps, threads);
+                    let res = pagerank_parallel(
+                        &graph,
+                        alpha,
+                        iters,
+                        eps,
+                        dangling,
+                        personalization,
+                        threads,
+                    );
+                    let duration = start.elapsed();
+                    println!("PageRank finished in: {:?}", duration);
+                    res
+                }
+                "pull" => {
+                    let threads = threads.unwrap_or(8);
+                    let start = std::time::Instant::now();
+                    graph.build_reverse();
+                    println!("In-edges built in: {:?}", start.elapsed());
+
+                    println!("Running pull PageRank with {} threads...", threads);
+                    let start = std::time::Instant::now();
+                    let res = pagerank_pull(
+                        &graph,
+                        alpha,
+                        iters,
+                        eps,
+                        dangling,
+                        personalization,
+                        threads,
+                    );
                     let duration = start.elapsed();
                     println!("PageRank finished in: {:?}", duration);
                     res
                 }
                 _ => {
-                    eprintln!("Error: mode must be 'seq' or 'par'");
+                    eprintln!("Error: mode must be 'seq', 'par' or 'pull'");
+                    std::process::exit(1);
+                }
+            };
+
+            match save_pagerank_result(&graph, &result, &out, &options) {
+                Ok(_) => println!("Result saved to: {}", out),
+                Err(e) => {
+                    eprintln!("Error saving result: {}", e);
+                    std::process::exit(1);
+                }
+            }
+
+            print_pagerank_stats(&graph, &result);
+        }
+
+        Commands::Resilience {
+            load,
+            threads,
+            order,
+            steps,
+            samples,
+            seed,
+            out,
+        } => {
+            let Some(order) = RemovalOrder::parse(&order) else {
+                eprintln!("Error: order must be 'random', 'degree', 'pagerank' or 'betweenness'");
+                std::process::exit(1);
+            };
+
+            let mut graph = load_graph(&load);
+            let threads = threads.unwrap_or(8);
+
+            let start = std::time::Instant::now();
+            graph.build_reverse();
+            println!("In-edges built in: {:?}", start.elapsed());
+
+            let start = std::time::Instant::now();
+            let nodes = removal_order(&graph, order, samples, seed, threads);
+            println!("Removal order built in: {:?}", start.elapsed());
+
+            println!("Removing nodes by {:?} in {} steps...", order, steps.max(1));
+            let start = std::time::Instant::now();
+            let result = resilience(&graph, &nodes, steps);
+            println!("Resilience finished in: {:?}", start.elapsed());
+
+            println!();
+            if let Err(e) = write_steps(&result, graph.num_nodes, &mut std::io::stdout()) {
+                eprintln!("Error writing curve: {}", e);
+                std::process::exit(1);
+            }
+            println!(
+                "Robustness (mean largest component fraction): {:.4}",
+                robustness(&result, graph.num_nodes)
+            );
+
+            if let Some(path) = out {
+                save_lines(&path, "Curve", |w| write_steps(&result, graph.num_nodes, w));
+            }
+        }
+
+        Commands::Stats {
+            load,
+            threads,
+            out,
+            format,
+        } => {
+            let json = match format.as_str() {
+                "text" => false,
+                "json" => true,
+                _ => {
+                    eprintln!("Error: stats format must be 'text' or 'json'");
                     std::process::exit(1);
                 }
             };
 
-            // match save_pagerank_result(&result, &out) {
-            //     Ok(_) => println!("Result saved to: {}", out),
-            //     Err(e) => {
-            //         eprintln!("Error saving result: {}", e);
-            //         std::process::exit(1);
-            //     }
-            // }
+            let graph = load_graph(&load);
+
+            let threads = threads.unwrap_or(8);
+            println!("Computing statistics with {} threads...", threads);
+            let start = std::time::Instant::now();
+            let stats = graph_stats(&graph, threads);
+            println!("Stats finished in: {:?}", start.elapsed());
+
+            println!();
+            if let Err(e) = write_stats_text(&stats, &mut std::io::stdout()) {
+                eprintln!("Error writing stats: {}", e);
+                std::process::exit(1);
+            }
+
+            if let Some(out) = out {
+                let result = save_stats(&stats, &out, json);
+                match result {
+                    Ok(_) => println!("Stats saved to: {}", out),
+                    Err(e) => {
+                        eprintln!("Error saving stats: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+        }
+
+        Commands::Convert {
+            load,
+            output,
+            format,
+        } => {
+            let format = match format {
+                Some(name) => graph_format(&name),
+                None => GraphFormat::from_path(&output).unwrap_or(GraphFormat::Binary),
+            };
+            let graph = load_graph(&load);
+
+            let start = std::time::Instant::now();
+            match write_graph(&graph, &output, format) {
+                Ok(_) => println!(
+                    "Graph saved to: {} ({:?}) in {:?}",
+                    output,
+                    format,
+                    start.elapsed()
+                ),
+                Err(e) => {
+                    eprintln!("Error saving graph: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Clean {
+            mut load,
+            output,
+            format,
+        } => {
+            let format = match format {
+                Some(name) => graph_format(&name),
+                None => GraphFormat::from_path(&output).unwrap_or(GraphFormat::EdgeList),
+            };
+            if !clean_options(&load.clean).any() {
+                load.clean.clean = true;
+            }
+            let graph = load_graph(&load);
 
-            print_pagerank_stats(&result);
+            let start = std::time::Instant::now();
+            match write_graph(&graph, &output, format) {
+                Ok(_) => println!(
+                    "Graph saved to: {} ({:?}) in {:?}",
+                    output,
+                    format,
+                    start.elapsed()
+                ),
+                Err(e) => {
+                    eprintln!("Error saving graph: {}", e);
+                    std::process::exit(1);
+                }
+            }
         }
 
         Commands::Generate {
@@ -208,16 +814,25 @@ fn main() {
             num_edges,
             num_components,
             output,
+            format,
+            undirected,
         } => {
             use graph_generator::*;
 
+            let format = match format {
+                Some(name) => graph_format(&name),
+                None => GraphFormat::from_path(&output).unwrap_or(GraphFormat::EdgeList),
+            };
+
             let result = match graph_type.as_str() {
-                "line" => generate_line(num_nodes, &output),
-                "star" => generate_star(num_nodes, &output),
-                "complete" => generate_complete(num_nodes, &output),
-                "cycle" => generate_cycle(num_nodes, &output),
+                "line" => generate_line(num_nodes, &output, format, undirected),
+                "star" => generate_star(num_nodes, &output, format, undirected),
+                "complete" => generate_complete(num_nodes, &output, format, undirected),
+                "cycle" => generate_cycle(num_nodes, &output, format, undirected),
                 "random" => match num_edges {
-                    Some(edges) => generate_random_parallel(num_nodes, edges, &output),
+                    Some(edges) => {
+                        generate_random_parallel(num_nodes, edges, &output, format, undirected)
+                    }
                     None => {
                         eprintln!("Error: random graph demands number of edges");
                         eprintln!("   Example: cargo run -- generate random 100 500 output.txt");
@@ -226,7 +841,7 @@ fn main() {
                 },
                 "disconnected" => match (num_edges, num_components) {
                     (Some(edges), Some(comps)) => {
-                        generate_disconnected(num_nodes, edges, comps, &output)
+                        generate_disconnected(num_nodes, edges, comps, &output, format, undirected)
                     }
                     _ => {
                         eprintln!(
@@ -255,54 +870,501 @@ fn main() {
     }
 }
 
-fn save_bfs_result(result: &[i32], path: &str) -> std::io::Result<()> {
-    let mut file = File::create(path)?;
+fn load_graph(load: &LoadArgs) -> Graph {
+    println!("Loading graph from: {}", load.input);
+    let start = std::time::Instant::now();
+    let format = match &load.input_format {
+        Some(name) => graph_format(name),
+        None => detect_input(&load.input),
+    };
+    let options = LoadOptions {
+        relabel: load.relabel,
+        lenient: load.lenient,
+        undirected: load.undirected,
+        verify: load.verify,
+    };
+    let result = read_graph(&load.input, format, &options);
+
+    match result {
+        Ok((g, skipped)) => {
+            if !skipped.is_empty() {
+                println!("Skipped {} bad lines:", skipped.count);
+                for (problem, count) in &skipped.by_kind {
+                    println!("   {}: {}", problem, count);
+                }
+                for e in skipped.lines.iter().take(SUMMARY_LINES) {
+                    println!("   line {}: {} '{}'", e.line, e.problem, e.text);
+                }
+                if skipped.count > SUMMARY_LINES {
+                    println!("   ... and {} more", skipped.count - SUMMARY_LINES);
+                }
+            }
+            let mut kind = Vec::new();
+            if g.is_weighted() {
+                kind.push("weighted");
+            }
+            if load.undirected {
+                // every undirected edge is counted once per direction
+                kind.push("undirected");
+            }
+            println!(
+                "Graph loaded: {} nodes, {} edges{}",
+                g.num_nodes,
+                g.num_edges(),
+                if kind.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({})", kind.join(", "))
+                }
+            );
+            // kept apart from the "finished in" line, which only times the algorithm
+            println!("Load time: {:?}", start.elapsed());
+
+            let steps = clean_options(&load.clean);
+            if steps.any() {
+                let mut g = g;
+                let start = std::time::Instant::now();
+                let report = g.clean(&steps);
+                println!(
+                    "Removed {} self-loops, {} duplicate edges, {} isolated nodes",
+                    report.self_loops, report.duplicates, report.isolated
+                );
+                println!(
+                    "Graph cleaned: {} nodes, {} edges",
+                    g.num_nodes,
+                    g.num_edges()
+                );
+                println!("Clean time: {:?}", start.elapsed());
+                return g;
+            }
+            g
+        }
+        Err(e) => {
+            eprintln!("Error while loading graph: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+// plain text file written line by line, exits on error
+fn save_lines<F>(path: &str, what: &str, write: F)
+where
+    F: FnOnce(&mut dyn Write) -> std::io::Result<()>,
+{
+    let result = std::fs::File::create(path).and_then(|file| {
+        let mut w = std::io::BufWriter::new(file);
+        write(&mut w)?;
+        w.flush()
+    });
+    match result {
+        Ok(_) => println!("{} saved to: {}", what, path),
+        Err(e) => {
+            eprintln!("Error saving {}: {}", what.to_lowercase(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn clean_options(args: &CleanArgs) -> CleanOptions {
+    CleanOptions {
+        sort: args.sort || args.clean,
+        dedup: args.dedup || args.clean,
+        self_loops: args.drop_self_loops || args.clean,
+        isolated: args.drop_isolated || args.clean,
+    }
+}
+
+fn graph_format(name: &str) -> GraphFormat {
+    match GraphFormat::parse(name) {
+        Some(f) => f,
+        None => {
+            eprintln!(
+                "Error: graph format must be 'edgelist', 'snap', 'mtx', 'dimacs', 'metis', 'graphml' or 'binary'"
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+// node id as given on the command line (original id when relabeled)
+fn resolve_node(graph: &Graph, name: &str) -> usize {
+    match graph.resolve_nodes(&[name.to_string()]) {
+        Ok(nodes) => nodes[0],
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn output_options(format: &str, compress: Option<&str>, out: &str) -> OutputOptions {
+    let format = match Format::parse(format) {
+        Some(f) => f,
+        None => {
+            eprintln!("Error: format must be 'text', 'csv', 'json' or 'jsonl'");
+            std::process::exit(1);
+        }
+    };
+
+    let compression = match compress {
+        Some(name) => match Compression::parse(name) {
+            Some(c) => c,
+            None => {
+                eprintln!("Error: compression must be 'none', 'gzip' or 'zstd'");
+                std::process::exit(1);
+            }
+        },
+        None => Compression::from_path(out),
+    };
+
+    OutputOptions {
+        format,
+        compression,
+    }
+}
+
+fn run_multi_bfs(
+    graph: &Graph,
+    sources: &[usize],
+    mode: &str,
+    threads: Option<usize>,
+    parents: bool,
+    out: &str,
+    options: &OutputOptions,
+) {
+    if parents {
+        eprintln!("Error: --parents works only with a single --source");
+        std::process::exit(1);
+    }
+
+    let (result, claimed) = match mode {
+        "seq" => {
+            println!(
+                "Running seq multi-source BFS from {} nodes...",
+                sources.len()
+            );
+            let start = std::time::Instant::now();
+            let res = bfs_multi_sequential(graph, sources);
+            let duration = start.elapsed();
+            println!("BFS finished in: {:?}", duration);
+            res
+        }
+        "par" => {
+            let threads = threads.unwrap_or(8);
+            println!(
+                "Running parallel multi-source BFS from {} nodes with {} threads...",
+                sources.len(),
+                threads
+            );
+            let start = std::time::Instant::now();
+            let res = bfs_multi_parallel(graph, sources, threads);
+            let duration = start.elapsed();
+            println!("BFS finished in: {:?}", duration);
+            res
+        }
+        _ => {
+            eprintln!("Error: multi-source BFS mode has to be either 'seq' or 'par'");
+            std::process::exit(1);
+        }
+    };
+
+    match save_multi_bfs_result(graph, &result, &claimed, out, options) {
+        Ok(_) => println!("Result saved in: {}", out),
+        Err(e) => {
+            eprintln!("Error while saving results: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    print_multi_bfs_stats(graph, &result, &claimed);
+}
+
+// nodes given inline and in a file are merged, None if neither is set
+fn load_node_lists(
+    graph: &Graph,
+    list: Option<&str>,
+    file: Option<&str>,
+) -> Option<Vec<(usize, f64)>> {
+    if list.is_none() && file.is_none() {
+        return None;
+    }
 
-    for (node, dist) in result.iter().enumerate() {
-        writeln!(file, "{} {}", node, dist)?;
+    let mut seeds = Vec::new();
+    if let Some(list) = list {
+        match parse_node_list(list) {
+            Ok(nodes) => seeds.extend(nodes),
+            Err(e) => {
+                eprintln!("Error parsing node list: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+    if let Some(path) = file {
+        match read_node_list(path) {
+            Ok(nodes) => seeds.extend(nodes),
+            Err(e) => {
+                eprintln!("Error reading node list file: {}", e);
+                std::process::exit(1);
+            }
+        }
     }
 
-    Ok(())
+    let names: Vec<String> = seeds.iter().map(|(name, _)| name.clone()).collect();
+    match graph.resolve_nodes(&names) {
+        Ok(nodes) => Some(
+            nodes
+                .into_iter()
+                .zip(seeds.iter().map(|&(_, w)| w))
+                .collect(),
+        ),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
 }
 
-fn save_wcc_result(result: &[usize], path: &str) -> std::io::Result<()> {
-    let mut file = File::create(path)?;
-    for (node, comp) in result.iter().enumerate() {
-        writeln!(file, "{} {}", node, comp)?;
+fn save_bfs_result(
+    graph: &Graph,
+    result: &[i32],
+    parent: Option<&[usize]>,
+    path: &str,
+    options: &OutputOptions,
+) -> std::io::Result<()> {
+    let labels = graph.labels();
+    match parent {
+        Some(parent) => {
+            // -1 for the source and unreachable nodes (NO_PARENT)
+            let parent = Nodes {
+                ids: parent,
+                labels,
+            };
+            save_columns(
+                path,
+                options,
+                labels,
+                &[("distance", &result), ("parent", &parent)],
+            )
+        }
+        None => save_columns(path, options, labels, &[("distance", &result)]),
     }
-    Ok(())
 }
 
-fn save_pagerank_result(result: &[f64], path: &str) -> std::io::Result<()> {
-    let mut file = File::create(path)?;
-    for (node, rank) in result.iter().enumerate() {
-        writeln!(file, "{} {:.10}", node, rank)?;
+// unreachable nodes are written as -1, same as in BFS results
+fn save_multi_bfs_result(
+    graph: &Graph,
+    result: &[i32],
+    claimed: &[usize],
+    path: &str,
+    options: &OutputOptions,
+) -> std::io::Result<()> {
+    // -1 for unreachable nodes (NO_SOURCE)
+    let claimed = Nodes {
+        ids: claimed,
+        labels: graph.labels(),
+    };
+    save_columns(
+        path,
+        options,
+        graph.labels(),
+        &[("distance", &result), ("source", &claimed)],
+    )
+}
+
+fn save_sssp_result(
+    graph: &Graph,
+    result: &[f64],
+    path: &str,
+    options: &OutputOptions,
+) -> std::io::Result<()> {
+    let dist: Vec<f64> = result
+        .iter()
+        .map(|&d| if d.is_finite() { d } else { -1.0 })
+        .collect();
+    save_columns(
+        path,
+        options,
+        graph.labels(),
+        &[("distance", &dist.as_slice())],
+    )
+}
+
+// dense labels are plain numbers, the others are node ids
+fn save_wcc_result(
+    graph: &Graph,
+    result: &[usize],
+    labeling: Labeling,
+    path: &str,
+    options: &OutputOptions,
+) -> std::io::Result<()> {
+    if labeling == Labeling::Dense {
+        return save_columns(path, options, graph.labels(), &[("component", &result)]);
     }
-    Ok(())
+    let component = Nodes {
+        ids: result,
+        labels: graph.labels(),
+    };
+    save_columns(path, options, graph.labels(), &[("component", &component)])
+}
+
+fn save_scc_result(
+    graph: &Graph,
+    result: &[usize],
+    path: &str,
+    options: &OutputOptions,
+) -> std::io::Result<()> {
+    let component = Nodes {
+        ids: result,
+        labels: graph.labels(),
+    };
+    save_columns(path, options, graph.labels(), &[("component", &component)])
+}
+
+fn save_pagerank_result(
+    graph: &Graph,
+    result: &[f64],
+    path: &str,
+    options: &OutputOptions,
+) -> std::io::Result<()> {
+    save_columns(path, options, graph.labels(), &[("rank", &result)])
 }
 
-fn print_bfs_stats(result: &[i32], source: usize) {
+fn print_bfs_stats(graph: &Graph, result: &[i32], source: usize) {
     let reachable = result.iter().filter(|&&d| d != -1).count();
     let unreachable = result.iter().filter(|&&d| d == -1).count();
     let max_dist = result.iter().filter(|&&d| d != -1).max().unwrap_or(&0);
 
     println!("\nStatistics:");
-    println!("   Source node: {}", source);
+    println!("   Source node: {}", graph.label(source));
     println!("   Reachable nodes: {}", reachable);
     println!("   Unreachable nodes: {}", unreachable);
     println!("   Max dist: {}", max_dist);
 }
 
-fn print_wcc_stats(result: &[usize]) {
-    use std::collections::HashSet;
-    let components: HashSet<_> = result.iter().collect();
+fn print_multi_bfs_stats(graph: &Graph, result: &[i32], claimed: &[usize]) {
+    use std::collections::HashMap;
+
+    let mut per_source: HashMap<usize, usize> = HashMap::new();
+    for &c in claimed.iter().filter(|&&c| c != NO_SOURCE) {
+        *per_source.entry(c).or_insert(0) += 1;
+    }
+    let mut per_source: Vec<(usize, usize)> = per_source.into_iter().collect();
+    per_source.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let reachable = result.iter().filter(|&&d| d != -1).count();
+    let max_dist = result.iter().max().unwrap_or(&0);
+
+    println!("\nStatistics:");
+    println!("   Sources: {}", per_source.len());
+    println!("   Reachable nodes: {}", reachable);
+    println!("   Unreachable nodes: {}", result.len() - reachable);
+    println!("   Max dist: {}", max_dist);
+    println!("   Nodes claimed per source (top 10):");
+    for (source, count) in per_source.iter().take(10) {
+        println!("     Node {}: {}", graph.label(*source), count);
+    }
+}
+
+fn print_sssp_stats(graph: &Graph, result: &[f64], source: usize) {
+    let reachable: Vec<f64> = result.iter().copied().filter(|d| d.is_finite()).collect();
+    let max_dist = reachable.iter().copied().fold(0.0, f64::max);
+
+    println!("\nStatistics:");
+    println!("   Source node: {}", graph.label(source));
+    println!("   Reachable nodes: {}", reachable.len());
+    println!("   Unreachable nodes: {}", result.len() - reachable.len());
+    println!("   Max dist: {:.6}", max_dist);
+}
+
+fn print_wcc_stats(
+    graph: &Graph,
+    result: &[usize],
+    labeling: Labeling,
+    top: usize,
+    members: Option<&str>,
+) {
+    let summary = component_summary(result, top);
+
+    println!("\nStatistics:");
+    println!("   Total nodes: {}", result.len());
+    println!("   Number of components: {}", summary.count);
+    println!(
+        "   Largest component: {} ({:.2}% of nodes)",
+        summary.largest,
+        summary.largest_fraction * 100.0
+    );
+    println!("   Size distribution (size: components):");
+    for &(size, count) in summary.distribution.iter().take(20) {
+        println!("      {}: {}", size, count);
+    }
+    if summary.distribution.len() > 20 {
+        println!("      ... {} more sizes", summary.distribution.len() - 20);
+    }
+    println!("   Top {} components (label: size):", summary.top.len());
+    for nodes in &summary.top {
+        let label = match labeling {
+            Labeling::Dense => result[nodes[0]].to_string(),
+            _ => graph.label(result[nodes[0]]),
+        };
+        println!("      {}: {}", label, nodes.len());
+    }
+
+    if let Some(path) = members {
+        save_lines(path, "Members", |w| {
+            for (rank, nodes) in summary.top.iter().enumerate() {
+                for &u in nodes {
+                    writeln!(w, "{} {}", rank, graph.label(u))?;
+                }
+            }
+            Ok(())
+        });
+    }
+}
+
+fn print_critical_stats(graph: &Graph, result: &Critical) {
+    let mut cut = result.articulation_points();
+    cut.sort_by_key(|&u| std::cmp::Reverse(result.cut_off[u]));
+
+    println!("\nStatistics:");
+    println!("   Articulation points: {}", cut.len());
+    println!("   Bridges: {}", result.bridges.len());
+    println!("   Biconnected components: {}", result.blocks.len());
+    println!(
+        "   Largest biconnected component: {}",
+        result.blocks.iter().map(Vec::len).max().unwrap_or(0)
+    );
+    if !cut.is_empty() {
+        println!("   Top cut vertices (nodes cut off):");
+        for &u in cut.iter().take(10) {
+            println!("      {} {}", graph.label(u), result.cut_off[u]);
+        }
+    }
+}
+
+fn print_scc_stats(result: &[usize]) {
+    use std::collections::HashMap;
+    let mut sizes: HashMap<usize, usize> = HashMap::new();
+    for &comp in result {
+        *sizes.entry(comp).or_insert(0) += 1;
+    }
+
+    let cyclic: Vec<usize> = sizes.values().copied().filter(|&s| s > 1).collect();
 
     println!("\nStatistics:");
     println!("   Total nodes: {}", result.len());
-    println!("   Number of components: {}", components.len());
+    println!("   Number of components: {}", sizes.len());
+    println!(
+        "   Largest component: {}",
+        sizes.values().max().unwrap_or(&0)
+    );
+    println!("   Cyclic components (size > 1): {}", cyclic.len());
+    println!(
+        "   Nodes in cyclic components: {}",
+        cyclic.iter().sum::<usize>()
+    );
 }
 
-fn print_pagerank_stats(result: &[f64]) {
+fn print_pagerank_stats(graph: &Graph, result: &[f64]) {
     use crate::pagerank::top_nodes;
 
     let sum: f64 = result.iter().sum();
@@ -313,6 +1375,6 @@ fn print_pagerank_stats(result: &[f64]) {
     println!("   Sum of ranks: {:.10}", sum);
     println!("   Top 10 nodes:");
     for (i, (node, rank)) in top.iter().enumerate() {
-        println!("     {}. Node {}: {:.6}", i + 1, node, rank);
+        println!("     {}. Node {}: {:.6}", i + 1, graph.label(*node), rank);
     }
 }
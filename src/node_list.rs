@@ -0,0 +1,78 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+// node ids are kept as written, Graph::resolve_nodes maps them to nodes
+
+// inline list "3,7:2.5,9", weight is optional and defaults to 1
+pub fn parse_node_list(list: &str) -> Result<Vec<(String, f64)>, Box<dyn Error>> {
+    let mut nodes = Vec::new();
+
+    for item in list.split(',') {
+        let item = item.trim();
+        if item.is_empty() {
+            continue;
+        }
+
+        nodes.push(match item.split_once(':') {
+            Some((node, weight)) => (node.trim().to_string(), weight.trim().parse()?),
+            None => (item.to_string(), 1.0),
+        });
+    }
+
+    Ok(nodes)
+}
+
+// one node per line, optionally followed by its weight
+pub fn read_node_list(path: &str) -> Result<Vec<(String, f64)>, Box<dyn Error>> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+    let mut nodes = Vec::new();
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() || line.starts_with("//") {
+            continue;
+        }
+
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        match parts.len() {
+            1 => nodes.push((parts[0].to_string(), 1.0)),
+            2 => nodes.push((parts[0].to_string(), parts[1].parse()?)),
+            _ => return Err(format!("bad node list line: '{}'", line).into()),
+        }
+    }
+
+    Ok(nodes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_node_list() {
+        let nodes = parse_node_list("3, web-01:2.5,9,").unwrap();
+        assert_eq!(
+            nodes,
+            vec![
+                ("3".to_string(), 1.0),
+                ("web-01".to_string(), 2.5),
+                ("9".to_string(), 1.0)
+            ]
+        );
+
+        assert!(parse_node_list("3:abc").is_err());
+    }
+
+    #[test]
+    fn test_read_node_list() {
+        let path = "test_node_list.txt";
+        std::fs::write(path, "// alarms\n4\n2 0.5\n\n").unwrap();
+
+        let nodes = read_node_list(path).unwrap();
+        assert_eq!(nodes, vec![("4".to_string(), 1.0), ("2".to_string(), 0.5)]);
+
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,394 @@
+use flate2::Compression as GzLevel;
+use flate2::write::GzEncoder;
+use std::fs::File;
+use std::io::{BufWriter, Result, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Text,
+    Csv,
+    Json,
+    JsonLines,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "text" | "txt" => Some(Format::Text),
+            "csv" => Some(Format::Csv),
+            "json" => Some(Format::Json),
+            "jsonl" | "ndjson" => Some(Format::JsonLines),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "none" => Some(Compression::None),
+            "gzip" | "gz" => Some(Compression::Gzip),
+            "zstd" | "zst" => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+
+    // used when --compress is not given, out.txt.gz / out.csv.zst
+    pub fn from_path(path: &str) -> Self {
+        if path.ends_with(".gz") {
+            Compression::Gzip
+        } else if path.ends_with(".zst") {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+}
+
+pub struct OutputOptions {
+    pub format: Format,
+    pub compression: Compression,
+}
+
+// single value of a result column, written the same way in every format
+pub trait Value {
+    fn write_value(&self, format: Format, w: &mut dyn Write) -> Result<()>;
+}
+
+impl Value for i32 {
+    fn write_value(&self, _format: Format, w: &mut dyn Write) -> Result<()> {
+        write!(w, "{}", self)
+    }
+}
+
+impl Value for usize {
+    fn write_value(&self, _format: Format, w: &mut dyn Write) -> Result<()> {
+        write!(w, "{}", self)
+    }
+}
+
+// JSON has no NaN or infinity, they become null there
+impl Value for f64 {
+    fn write_value(&self, format: Format, w: &mut dyn Write) -> Result<()> {
+        match format {
+            Format::Json | Format::JsonLines if !self.is_finite() => write!(w, "null"),
+            _ => write!(w, "{:.10}", self),
+        }
+    }
+}
+
+pub trait Column {
+    fn len(&self) -> usize;
+    fn write_at(&self, index: usize, format: Format, w: &mut dyn Write) -> Result<()>;
+}
+
+impl<T: Value> Column for &[T] {
+    fn len(&self) -> usize {
+        <[T]>::len(self)
+    }
+
+    fn write_at(&self, index: usize, format: Format, w: &mut dyn Write) -> Result<()> {
+        self[index].write_value(format, w)
+    }
+}
+
+// column whose values are node ids (parent, source, component root), written
+// with their original ids, usize::MAX (no such node) is written as -1
+pub struct Nodes<'a> {
+    pub ids: &'a [usize],
+    pub labels: Option<&'a [String]>,
+}
+
+impl Column for Nodes<'_> {
+    fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    fn write_at(&self, index: usize, format: Format, w: &mut dyn Write) -> Result<()> {
+        match self.ids[index] {
+            usize::MAX => write!(w, "-1"),
+            id => write_node(w, format, self.labels, id),
+        }
+    }
+}
+
+// numeric ids are written as numbers, labels as strings (quoted where the format needs it)
+fn write_node(
+    w: &mut dyn Write,
+    format: Format,
+    labels: Option<&[String]>,
+    node: usize,
+) -> Result<()> {
+    let label = match labels {
+        Some(labels) => &labels[node],
+        None => return write!(w, "{}", node),
+    };
+
+    match format {
+        Format::Text => write!(w, "{}", label),
+        Format::Csv => {
+            if label.contains([',', '"']) {
+                write!(w, "\"{}\"", label.replace('"', "\"\""))
+            } else {
+                write!(w, "{}", label)
+            }
+        }
+        Format::Json | Format::JsonLines => {
+            write!(w, "\"")?;
+            for c in label.chars() {
+                match c {
+                    '"' => write!(w, "\\\"")?,
+                    '\\' => write!(w, "\\\\")?,
+                    c if c.is_control() => write!(w, "\\u{:04x}", c as u32)?,
+                    c => write!(w, "{}", c)?,
+                }
+            }
+            write!(w, "\"")
+        }
+    }
+}
+
+enum Sink {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+    Zstd(zstd::Encoder<'static, BufWriter<File>>),
+}
+
+impl Sink {
+    fn create(path: &str, compression: Compression) -> Result<Self> {
+        let file = BufWriter::new(File::create(path)?);
+        Ok(match compression {
+            Compression::None => Sink::Plain(file),
+            Compression::Gzip => Sink::Gzip(GzEncoder::new(file, GzLevel::default())),
+            Compression::Zstd => Sink::Zstd(zstd::Encoder::new(file, 0)?),
+        })
+    }
+
+    // encoders have to write their trailer, dropping them would hide the error
+    fn finish(self) -> Result<()> {
+        match self {
+            Sink::Plain(mut w) => w.flush(),
+            Sink::Gzip(w) => w.finish()?.flush(),
+            Sink::Zstd(w) => w.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        match self {
+            Sink::Plain(w) => w.write(buf),
+            Sink::Gzip(w) => w.write(buf),
+            Sink::Zstd(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        match self {
+            Sink::Plain(w) => w.flush(),
+            Sink::Gzip(w) => w.flush(),
+            Sink::Zstd(w) => w.flush(),
+        }
+    }
+}
+
+// one row per node: node id (original id if labels are given) followed by the given columns
+pub fn save_columns(
+    path: &str,
+    options: &OutputOptions,
+    labels: Option<&[String]>,
+    columns: &[(&str, &dyn Column)],
+) -> Result<()> {
+    let mut sink = Sink::create(path, options.compression)?;
+    write_columns(&mut sink, options.format, labels, columns)?;
+    sink.finish()
+}
+
+fn write_columns(
+    w: &mut dyn Write,
+    format: Format,
+    labels: Option<&[String]>,
+    columns: &[(&str, &dyn Column)],
+) -> Result<()> {
+    let rows = columns.first().map_or(0, |(_, c)| c.len());
+
+    match format {
+        Format::Text => {
+            for row in 0..rows {
+                write_node(w, format, labels, row)?;
+                for (_, column) in columns {
+                    write!(w, " ")?;
+                    column.write_at(row, format, w)?;
+                }
+                writeln!(w)?;
+            }
+        }
+        Format::Csv => {
+            write!(w, "node")?;
+            for (name, _) in columns {
+                write!(w, ",{}", name)?;
+            }
+            writeln!(w)?;
+
+            for row in 0..rows {
+                write_node(w, format, labels, row)?;
+                for (_, column) in columns {
+                    write!(w, ",")?;
+                    column.write_at(row, format, w)?;
+                }
+                writeln!(w)?;
+            }
+        }
+        Format::Json | Format::JsonLines => {
+            let json = format == Format::Json;
+            if json {
+                writeln!(w, "[")?;
+            }
+
+            for row in 0..rows {
+                write!(w, "{{\"node\":")?;
+                write_node(w, format, labels, row)?;
+                for (name, column) in columns {
+                    write!(w, ",\"{}\":", name)?;
+                    column.write_at(row, format, w)?;
+                }
+                write!(w, "}}")?;
+
+                if json && row + 1 < rows {
+                    write!(w, ",")?;
+                }
+                writeln!(w)?;
+            }
+
+            if json {
+                writeln!(w, "]")?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(format: Format, columns: &[(&str, &dyn Column)]) -> String {
+        render_labeled(format, None, columns)
+    }
+
+    fn render_labeled(
+        format: Format,
+        labels: Option<&[String]>,
+        columns: &[(&str, &dyn Column)],
+    ) -> String {
+        let mut buf = Vec::new();
+        write_columns(&mut buf, format, labels, columns).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn test_text_and_csv() {
+        let dist: &[i32] = &[0, 1, -1];
+
+        assert_eq!(
+            render(Format::Text, &[("distance", &dist)]),
+            "0 0\n1 1\n2 -1\n"
+        );
+        assert_eq!(
+            render(Format::Csv, &[("distance", &dist)]),
+            "node,distance\n0,0\n1,1\n2,-1\n"
+        );
+    }
+
+    #[test]
+    fn test_json_and_jsonl() {
+        let rank: &[f64] = &[0.5, 0.25];
+
+        assert_eq!(
+            render(Format::Json, &[("rank", &rank)]),
+            "[\n{\"node\":0,\"rank\":0.5000000000},\n{\"node\":1,\"rank\":0.2500000000}\n]\n"
+        );
+        assert_eq!(
+            render(Format::JsonLines, &[("rank", &rank)]),
+            "{\"node\":0,\"rank\":0.5000000000}\n{\"node\":1,\"rank\":0.2500000000}\n"
+        );
+
+        // NaN and infinity are not JSON
+        let broken: &[f64] = &[f64::NAN, f64::INFINITY];
+        assert_eq!(
+            render(Format::JsonLines, &[("rank", &broken)]),
+            "{\"node\":0,\"rank\":null}\n{\"node\":1,\"rank\":null}\n"
+        );
+        assert_eq!(render(Format::Text, &[("rank", &broken)]), "0 NaN\n1 inf\n");
+    }
+
+    #[test]
+    fn test_labels() {
+        let labels = vec!["web-01".to_string(), "db,\"main\"".to_string()];
+        let parent = Nodes {
+            ids: &[usize::MAX, 0],
+            labels: Some(&labels),
+        };
+
+        assert_eq!(
+            render_labeled(Format::Csv, Some(&labels), &[("parent", &parent)]),
+            "node,parent\nweb-01,-1\n\"db,\"\"main\"\"\",web-01\n"
+        );
+        assert_eq!(
+            render_labeled(Format::JsonLines, Some(&labels), &[("parent", &parent)]),
+            "{\"node\":\"web-01\",\"parent\":-1}\n{\"node\":\"db,\\\"main\\\"\",\"parent\":\"web-01\"}\n"
+        );
+
+        let parent = Nodes {
+            ids: &[usize::MAX, 0],
+            labels: None,
+        };
+        assert_eq!(render(Format::Text, &[("parent", &parent)]), "0 -1\n1 0\n");
+    }
+
+    #[test]
+    fn test_compressed_roundtrip() {
+        use std::io::Read;
+
+        let comp: &[usize] = &[0, 0, 2];
+        let options = |compression| OutputOptions {
+            format: Format::Text,
+            compression,
+        };
+
+        let path = "test_output_gzip.txt.gz";
+        save_columns(
+            path,
+            &options(Compression::Gzip),
+            None,
+            &[("component", &comp)],
+        )
+        .unwrap();
+        let mut text = String::new();
+        flate2::read::GzDecoder::new(File::open(path).unwrap())
+            .read_to_string(&mut text)
+            .unwrap();
+        assert_eq!(text, "0 0\n1 0\n2 2\n");
+        std::fs::remove_file(path).ok();
+
+        let path = "test_output_zstd.txt.zst";
+        save_columns(
+            path,
+            &options(Compression::Zstd),
+            None,
+            &[("component", &comp)],
+        )
+        .unwrap();
+        let bytes = zstd::decode_all(File::open(path).unwrap()).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(), "0 0\n1 0\n2 2\n");
+        std::fs::remove_file(path).ok();
+    }
+}
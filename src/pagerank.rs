@@ -2,30 +2,134 @@ use crate::graph::Graph;
 use rayon::prelude::*;
 use std::sync::atomic::{AtomicU64, Ordering};
 
-pub fn pagerank_sequential(graph: &Graph, alpha: f64, max_iters: usize, eps: f64) -> Vec<f64> {
+// what happens with the rank of nodes without out-edges
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Dangling {
+    // spread evenly over all nodes
+    Uniform,
+    // spread following the teleport vector
+    Personalization,
+    // kept by the dangling node itself
+    SelfLoop,
+    // dropped, ranks no longer sum to 1
+    Ignore,
+}
+
+impl Dangling {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "uniform" => Some(Dangling::Uniform),
+            "personalization" => Some(Dangling::Personalization),
+            "self-loop" => Some(Dangling::SelfLoop),
+            "ignore" => Some(Dangling::Ignore),
+            _ => None,
+        }
+    }
+}
+
+// teleport and dangling mass a node receives in one iteration, on top of pushed contributions
+struct Redistribution<'a> {
+    out_weight: &'a [f64],
+    rank: &'a [f64],
+    alpha: f64,
+    dangling: Dangling,
+    dangling_sum: f64,
+    // teleport vector summing to 1, uniform when None
+    personalization: Option<&'a [f64]>,
+}
+
+impl Redistribution<'_> {
+    #[inline]
+    fn share(&self, v: usize) -> f64 {
+        let n = self.out_weight.len() as f64;
+        let teleport = self.personalization.map_or(1.0 / n, |p| p[v]);
+
+        let dangling = match self.dangling {
+            Dangling::Uniform => self.alpha * self.dangling_sum / n,
+            Dangling::Personalization => self.alpha * self.dangling_sum * teleport,
+            Dangling::SelfLoop if self.out_weight[v] == 0.0 => self.alpha * self.rank[v],
+            Dangling::SelfLoop | Dangling::Ignore => 0.0,
+        };
+
+        (1.0 - self.alpha) * teleport + dangling
+    }
+}
+
+// teleport vector concentrated on the seeds, proportional to their weights
+pub fn personalization_vector(
+    num_nodes: usize,
+    seeds: &[(usize, f64)],
+) -> Result<Vec<f64>, String> {
+    let mut p = vec![0.0; num_nodes];
+
+    for &(node, weight) in seeds {
+        if num_nodes == 0 {
+            return Err(format!("seed node {} doesnt exist (graph is empty)", node));
+        }
+        if node >= num_nodes {
+            return Err(format!(
+                "seed node {} doesnt exist (max is {})",
+                node,
+                num_nodes - 1
+            ));
+        }
+        if !weight.is_finite() || weight < 0.0 {
+            return Err(format!("seed node {} has invalid weight {}", node, weight));
+        }
+        p[node] += weight;
+    }
+
+    let total: f64 = p.iter().sum();
+    if total <= 0.0 {
+        return Err("seed weights must sum to a positive value".to_string());
+    }
+
+    for x in &mut p {
+        *x /= total;
+    }
+    Ok(p)
+}
+
+pub fn pagerank_sequential(
+    graph: &Graph,
+    alpha: f64,
+    max_iters: usize,
+    eps: f64,
+    dangling: Dangling,
+    personalization: Option<&[f64]>,
+) -> Vec<f64> {
     let n = graph.num_nodes;
     let mut rank = vec![1.0 / n as f64; n];
     let mut new_rank = vec![0.0; n];
-
-    let teleport = (1.0 - alpha) / n as f64;
+    // out-degree, or total out-edge weight in weighted graphs
+    let out_weight: Vec<f64> = (0..n).map(|u| graph.out_weight(u)).collect();
 
     for iteration in 0..max_iters {
         new_rank.fill(0.0);
+        let mut dangling_sum = 0.0;
 
-        for u in 0..n {
-            let out_degree = graph.edges[u].len();
+        for (u, &r) in rank.iter().enumerate() {
+            if out_weight[u] > 0.0 {
+                let contribution = r / out_weight[u];
 
-            if out_degree > 0 {
-                let contribution = rank[u] / out_degree as f64;
-
-                for &v in &graph.edges[u] {
-                    new_rank[v] += contribution * alpha;
+                for (v, w) in graph.weighted_neighbors(u) {
+                    new_rank[v] += contribution * w * alpha;
                 }
+            } else {
+                dangling_sum += r;
             }
         }
 
-        for r in &mut new_rank {
-            *r += teleport;
+        let extra = Redistribution {
+            out_weight: &out_weight,
+            rank: &rank,
+            alpha,
+            dangling,
+            dangling_sum,
+            personalization,
+        };
+        for (v, r) in new_rank.iter_mut().enumerate() {
+            *r += extra.share(v);
         }
 
         let diff: f64 = rank
@@ -52,13 +156,15 @@ pub fn pagerank_parallel(
     alpha: f64,
     max_iters: usize,
     eps: f64,
+    dangling: Dangling,
+    personalization: Option<&[f64]>,
     num_threads: usize,
 ) -> Vec<f64> {
     rayon::ThreadPoolBuilder::new()
         .num_threads(num_threads)
         .build()
         .unwrap()
-        .install(|| pagerank_parallel_impl(graph, alpha, max_iters, eps))
+        .install(|| pagerank_parallel_impl(graph, alpha, max_iters, eps, dangling, personalization))
 }
 
 #[inline]
@@ -87,13 +193,22 @@ fn atomic_add_f64(atomic: &AtomicU64, increment: f64) {
     }
 }
 
-fn pagerank_parallel_impl(graph: &Graph, alpha: f64, max_iters: usize, eps: f64) -> Vec<f64> {
+fn pagerank_parallel_impl(
+    graph: &Graph,
+    alpha: f64,
+    max_iters: usize,
+    eps: f64,
+    dangling: Dangling,
+    personalization: Option<&[f64]>,
+) -> Vec<f64> {
     let n = graph.num_nodes;
     let mut rank = vec![1.0 / n as f64; n];
     let new_rank_atomic: Vec<AtomicU64> =
         (0..n).map(|_| AtomicU64::new(f64_to_bits(0.0))).collect();
-
-    let teleport = (1.0 - alpha) / n as f64;
+    let out_weight: Vec<f64> = (0..n)
+        .into_par_iter()
+        .map(|u| graph.out_weight(u))
+        .collect();
 
     for iteration in 0..max_iters {
         new_rank_atomic.par_iter().for_each(|atomic| {
@@ -101,23 +216,123 @@ fn pagerank_parallel_impl(graph: &Graph, alpha: f64, max_iters: usize, eps: f64)
         });
 
         (0..n).into_par_iter().for_each(|u| {
-            let out_degree = graph.edges[u].len();
-
-            if out_degree > 0 {
-                let contrib = rank[u] / out_degree as f64;
-                let weighted_contrib = alpha * contrib;
+            if out_weight[u] > 0.0 {
+                let contrib = rank[u] / out_weight[u];
 
-                for &v in &graph.edges[u] {
-                    atomic_add_f64(&new_rank_atomic[v], weighted_contrib);
+                for (v, w) in graph.weighted_neighbors(u) {
+                    atomic_add_f64(&new_rank_atomic[v], alpha * contrib * w);
                 }
             }
         });
 
+        let dangling_sum: f64 = (0..n)
+            .into_par_iter()
+            .filter(|&u| out_weight[u] == 0.0)
+            .map(|u| rank[u])
+            .sum();
+        let extra = Redistribution {
+            out_weight: &out_weight,
+            rank: &rank,
+            alpha,
+            dangling,
+            dangling_sum,
+            personalization,
+        };
+
         let new_rank: Vec<f64> = new_rank_atomic
             .par_iter()
-            .map(|atomic| {
+            .enumerate()
+            .map(|(v, atomic)| {
                 let val = f64_from_bits(atomic.load(Ordering::Acquire));
-                val + teleport
+                val + extra.share(v)
+            })
+            .collect();
+
+        let diff: f64 = rank
+            .par_iter()
+            .zip(&new_rank)
+            .map(|(old, new)| (old - new).abs())
+            .sum();
+
+        if diff < eps {
+            println!("PageRank converged after {} iterations", iteration + 1);
+            return new_rank;
+        }
+
+        rank = new_rank;
+    }
+
+    println!("PageRank reached max iterations ({})", max_iters);
+    rank
+}
+
+// pull variant: every node gathers from its in-neighbors, so no atomics are needed
+// graph must have in-edges built (Graph::build_reverse)
+pub fn pagerank_pull(
+    graph: &Graph,
+    alpha: f64,
+    max_iters: usize,
+    eps: f64,
+    dangling: Dangling,
+    personalization: Option<&[f64]>,
+    num_threads: usize,
+) -> Vec<f64> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .unwrap()
+        .install(|| pagerank_pull_impl(graph, alpha, max_iters, eps, dangling, personalization))
+}
+
+fn pagerank_pull_impl(
+    graph: &Graph,
+    alpha: f64,
+    max_iters: usize,
+    eps: f64,
+    dangling: Dangling,
+    personalization: Option<&[f64]>,
+) -> Vec<f64> {
+    assert!(graph.has_reverse(), "pull PageRank needs in-edges");
+
+    let n = graph.num_nodes;
+    let mut rank = vec![1.0 / n as f64; n];
+    let mut contrib = vec![0.0; n];
+    let out_weight: Vec<f64> = (0..n)
+        .into_par_iter()
+        .map(|u| graph.out_weight(u))
+        .collect();
+
+    for iteration in 0..max_iters {
+        contrib.par_iter_mut().enumerate().for_each(|(u, c)| {
+            *c = if out_weight[u] > 0.0 {
+                rank[u] / out_weight[u]
+            } else {
+                0.0
+            };
+        });
+
+        let dangling_sum: f64 = (0..n)
+            .into_par_iter()
+            .filter(|&u| out_weight[u] == 0.0)
+            .map(|u| rank[u])
+            .sum();
+        let extra = Redistribution {
+            out_weight: &out_weight,
+            rank: &rank,
+            alpha,
+            dangling,
+            dangling_sum,
+            personalization,
+        };
+
+        let new_rank: Vec<f64> = (0..n)
+            .into_par_iter()
+            .map(|v| {
+                let sum: f64 = graph
+                    .weighted_in_neighbors(v)
+                    .map(|(u, w)| contrib[u] * w)
+                    .sum();
+                alpha * sum + extra.share(v)
             })
             .collect();
 
@@ -157,17 +372,35 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_pagerank_simple() {
-        let graph = Graph {
-            num_nodes: 3,
-            edges: vec![
-                vec![1], // 0→1
-                vec![2], // 1→2
-                vec![0], // 2→0
-            ],
+    fn test_pagerank_undirected_load() {
+        use crate::formats::{GraphFormat, LoadOptions, read_graph};
+
+        // path 0→1→2 with 2→1 already in the file, loaded as 0-1-2
+        let path = "test_pagerank_undirected.txt";
+        std::fs::write(path, "0 1\n1 2\n2 1\n").unwrap();
+        let options = LoadOptions {
+            undirected: true,
+            ..LoadOptions::default()
         };
+        let (graph, _) = read_graph(path, GraphFormat::EdgeList, &options).unwrap();
+        std::fs::remove_file(path).ok();
+
+        assert_eq!(graph.num_edges(), 4);
+        let ranks = pagerank_sequential(&graph, 0.85, 100, 1e-10, Dangling::Uniform, None);
+        assert!((ranks[0] - ranks[2]).abs() < 1e-9);
+        assert!(ranks[1] > ranks[0]);
+        assert!((ranks.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pagerank_simple() {
+        let graph = Graph::from_adjacency(vec![
+            vec![1], // 0→1
+            vec![2], // 1→2
+            vec![0], // 2→0
+        ]);
 
-        let ranks = pagerank_sequential(&graph, 0.85, 100, 1e-6);
+        let ranks = pagerank_sequential(&graph, 0.85, 100, 1e-6, Dangling::Uniform, None);
 
         assert!((ranks[0] - ranks[1]).abs() < 0.01);
         assert!((ranks[1] - ranks[2]).abs() < 0.01);
@@ -179,17 +412,14 @@ mod tests {
 
     #[test]
     fn test_pagerank_star() {
-        let graph = Graph {
-            num_nodes: 4,
-            edges: vec![
-                vec![1, 2, 3], // 0→1,2,3
-                vec![],        // 1
-                vec![],        // 2
-                vec![],        // 3
-            ],
-        };
+        let graph = Graph::from_adjacency(vec![
+            vec![1, 2, 3], // 0→1,2,3
+            vec![],        // 1
+            vec![],        // 2
+            vec![],        // 3
+        ]);
 
-        let ranks = pagerank_sequential(&graph, 0.85, 100, 1e-6);
+        let ranks = pagerank_sequential(&graph, 0.85, 100, 1e-6, Dangling::Uniform, None);
 
         assert!(ranks[0] < ranks[1]);
         assert!(ranks[0] < ranks[2]);
@@ -223,13 +453,10 @@ mod tests {
 
     #[test]
     fn test_pagerank_parallel_vs_sequential() {
-        let graph = Graph {
-            num_nodes: 4,
-            edges: vec![vec![1, 2], vec![3], vec![3], vec![0]],
-        };
+        let graph = Graph::from_adjacency(vec![vec![1, 2], vec![3], vec![3], vec![0]]);
 
-        let seq = pagerank_sequential(&graph, 0.85, 100, 1e-6);
-        let par = pagerank_parallel(&graph, 0.85, 100, 1e-6, 4);
+        let seq = pagerank_sequential(&graph, 0.85, 100, 1e-6, Dangling::Uniform, None);
+        let par = pagerank_parallel(&graph, 0.85, 100, 1e-6, Dangling::Uniform, None, 4);
 
         for i in 0..4 {
             assert!(
@@ -244,24 +471,21 @@ mod tests {
 
     #[test]
     fn test_pagerank_par_vs_seq_large() {
-        let graph = Graph {
-            num_nodes: 10,
-            edges: vec![
-                vec![1, 2, 3],
-                vec![4],
-                vec![5],
-                vec![6],
-                vec![7],
-                vec![8],
-                vec![9],
-                vec![0],
-                vec![0],
-                vec![0],
-            ],
-        };
-
-        let seq = pagerank_sequential(&graph, 0.85, 100, 1e-8);
-        let par = pagerank_parallel(&graph, 0.85, 100, 1e-8, 4);
+        let graph = Graph::from_adjacency(vec![
+            vec![1, 2, 3],
+            vec![4],
+            vec![5],
+            vec![6],
+            vec![7],
+            vec![8],
+            vec![9],
+            vec![0],
+            vec![0],
+            vec![0],
+        ]);
+
+        let seq = pagerank_sequential(&graph, 0.85, 100, 1e-8, Dangling::Uniform, None);
+        let par = pagerank_parallel(&graph, 0.85, 100, 1e-8, Dangling::Uniform, None, 4);
 
         let sum_seq: f64 = seq.iter().sum();
         let sum_par: f64 = par.iter().sum();
@@ -273,4 +497,198 @@ mod tests {
             assert!((seq[i] - par[i]).abs() < 1e-4);
         }
     }
+
+    #[test]
+    fn test_pagerank_pull_vs_sequential() {
+        let mut graph = Graph::from_adjacency(vec![
+            vec![1, 2, 3],
+            vec![4],
+            vec![5],
+            vec![6],
+            vec![7],
+            vec![8],
+            vec![9],
+            vec![0],
+            vec![0],
+            vec![0],
+        ]);
+        graph.build_reverse();
+
+        let seq = pagerank_sequential(&graph, 0.85, 100, 1e-8, Dangling::Uniform, None);
+        let pull = pagerank_pull(&graph, 0.85, 100, 1e-8, Dangling::Uniform, None, 4);
+
+        for i in 0..10 {
+            assert!(
+                (seq[i] - pull[i]).abs() < 1e-9,
+                "Node {}: seq={}, pull={}",
+                i,
+                seq[i],
+                pull[i]
+            );
+        }
+    }
+
+    #[test]
+    fn test_pagerank_pull_star_hub() {
+        // every leaf points to the hub, the case where push contends on one atomic
+        let mut adjacency = vec![vec![]; 50];
+        for leaf in adjacency.iter_mut().skip(1) {
+            leaf.push(0);
+        }
+        let mut graph = Graph::from_adjacency(adjacency);
+        graph.build_reverse();
+
+        let seq = pagerank_sequential(&graph, 0.85, 100, 1e-10, Dangling::Uniform, None);
+        let pull = pagerank_pull(&graph, 0.85, 100, 1e-10, Dangling::Uniform, None, 4);
+
+        assert_eq!(top_nodes(&pull, 1)[0].0, 0);
+        for i in 0..50 {
+            assert!((seq[i] - pull[i]).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_pagerank_dangling_sums_to_one() {
+        // line 0→1→2→3, node 3 is a sink
+        let mut graph = Graph::from_adjacency(vec![vec![1], vec![2], vec![3], vec![]]);
+        graph.build_reverse();
+
+        for dangling in [
+            Dangling::Uniform,
+            Dangling::Personalization,
+            Dangling::SelfLoop,
+        ] {
+            let seq = pagerank_sequential(&graph, 0.85, 200, 1e-12, dangling, None);
+            let par = pagerank_parallel(&graph, 0.85, 200, 1e-12, dangling, None, 4);
+            let pull = pagerank_pull(&graph, 0.85, 200, 1e-12, dangling, None, 4);
+
+            for ranks in [&seq, &par, &pull] {
+                let sum: f64 = ranks.iter().sum();
+                assert!((sum - 1.0).abs() < 1e-9, "{:?}: sum={}", dangling, sum);
+            }
+            for i in 0..4 {
+                assert!((seq[i] - par[i]).abs() < 1e-9);
+                assert!((seq[i] - pull[i]).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pagerank_dangling_ignore_leaks() {
+        let graph = Graph::from_adjacency(vec![vec![1], vec![2], vec![3], vec![]]);
+
+        let seq = pagerank_sequential(&graph, 0.85, 200, 1e-12, Dangling::Ignore, None);
+        let par = pagerank_parallel(&graph, 0.85, 200, 1e-12, Dangling::Ignore, None, 4);
+
+        let sum: f64 = seq.iter().sum();
+        assert!(sum < 0.9);
+        for i in 0..4 {
+            assert!((seq[i] - par[i]).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_pagerank_dangling_self_loop() {
+        // with self-loop the sink keeps its mass and ends up on top
+        let graph = Graph::from_adjacency(vec![vec![1], vec![2], vec![3], vec![]]);
+
+        let self_loop = pagerank_sequential(&graph, 0.85, 500, 1e-12, Dangling::SelfLoop, None);
+        let uniform = pagerank_sequential(&graph, 0.85, 500, 1e-12, Dangling::Uniform, None);
+
+        assert_eq!(top_nodes(&self_loop, 1)[0].0, 3);
+        assert!(self_loop[3] > uniform[3]);
+    }
+
+    #[test]
+    fn test_personalization_vector() {
+        let p = personalization_vector(4, &[(1, 1.0), (3, 3.0)]).unwrap();
+        assert_eq!(p, vec![0.0, 0.25, 0.0, 0.75]);
+
+        assert!(personalization_vector(4, &[(4, 1.0)]).is_err());
+        assert!(personalization_vector(4, &[(1, -1.0)]).is_err());
+        assert!(personalization_vector(4, &[(1, 0.0)]).is_err());
+        assert!(personalization_vector(0, &[(0, 1.0)]).is_err());
+    }
+
+    #[test]
+    fn test_personalized_pagerank() {
+        // two separate cycles, all teleport mass goes to the first one
+        let mut graph =
+            Graph::from_adjacency(vec![vec![1], vec![2], vec![0], vec![4], vec![5], vec![3]]);
+        graph.build_reverse();
+        let p = personalization_vector(6, &[(0, 1.0)]).unwrap();
+
+        let seq = pagerank_sequential(&graph, 0.85, 200, 1e-12, Dangling::Uniform, Some(&p));
+        let par = pagerank_parallel(&graph, 0.85, 200, 1e-12, Dangling::Uniform, Some(&p), 4);
+        let pull = pagerank_pull(&graph, 0.85, 200, 1e-12, Dangling::Uniform, Some(&p), 4);
+
+        assert_eq!(top_nodes(&seq, 1)[0].0, 0);
+        assert!(seq[1] > seq[2]);
+        assert!(seq[3..].iter().all(|&r| r < 1e-12));
+        for i in 0..6 {
+            assert!((seq[i] - par[i]).abs() < 1e-9);
+            assert!((seq[i] - pull[i]).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_personalized_dangling_goes_to_seeds() {
+        // 0→1, 1 is a sink, seed is 2
+        let graph = Graph::from_adjacency(vec![vec![1], vec![], vec![]]);
+        let p = personalization_vector(3, &[(2, 1.0)]).unwrap();
+
+        let ranks = pagerank_sequential(
+            &graph,
+            0.85,
+            200,
+            1e-12,
+            Dangling::Personalization,
+            Some(&p),
+        );
+
+        let sum: f64 = ranks.iter().sum();
+        assert!((sum - 1.0).abs() < 1e-9);
+        assert!(ranks[0] < 1e-12);
+        assert!(ranks[2] > ranks[1]);
+    }
+
+    #[test]
+    fn test_pagerank_weighted() {
+        // 0 sends 3/4 of its rank to 1 and 1/4 to 2, both point back to 0
+        let mut graph =
+            Graph::from_weighted_edges(3, &[(0, 1, 3.0), (0, 2, 1.0), (1, 0, 1.0), (2, 0, 5.0)]);
+        graph.build_reverse();
+
+        let seq = pagerank_sequential(&graph, 0.85, 200, 1e-12, Dangling::Uniform, None);
+        let par = pagerank_parallel(&graph, 0.85, 200, 1e-12, Dangling::Uniform, None, 4);
+        let pull = pagerank_pull(&graph, 0.85, 200, 1e-12, Dangling::Uniform, None, 4);
+
+        assert!(seq[1] > seq[2]);
+        let sum: f64 = seq.iter().sum();
+        assert!((sum - 1.0).abs() < 1e-9);
+        for i in 0..3 {
+            assert!((seq[i] - par[i]).abs() < 1e-9);
+            assert!((seq[i] - pull[i]).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_pagerank_unit_weights_match_unweighted() {
+        let plain = Graph::from_edges(4, &[(0, 1), (0, 2), (1, 3), (2, 3), (3, 0)]);
+        let weighted = Graph::from_weighted_edges(
+            4,
+            &[
+                (0, 1, 1.0),
+                (0, 2, 1.0),
+                (1, 3, 1.0),
+                (2, 3, 1.0),
+                (3, 0, 1.0),
+            ],
+        );
+
+        let a = pagerank_sequential(&plain, 0.85, 100, 1e-10, Dangling::Uniform, None);
+        let b = pagerank_sequential(&weighted, 0.85, 100, 1e-10, Dangling::Uniform, None);
+
+        assert_eq!(a, b);
+    }
 }
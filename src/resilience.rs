@@ -0,0 +1,336 @@
+use crate::graph::Graph;
+use crate::pagerank::{Dangling, pagerank_parallel};
+use crate::wcc::removal_curve;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::{SliceRandom, index};
+use rayon::prelude::*;
+use std::collections::VecDeque;
+use std::io::{Result, Write};
+
+// which nodes fail first, scores are computed once on the whole graph
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RemovalOrder {
+    Random,
+    // in + out degree
+    Degree,
+    PageRank,
+    // betweenness centrality from sampled sources
+    Betweenness,
+}
+
+impl RemovalOrder {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "random" => Some(RemovalOrder::Random),
+            "degree" => Some(RemovalOrder::Degree),
+            "pagerank" => Some(RemovalOrder::PageRank),
+            "betweenness" => Some(RemovalOrder::Betweenness),
+            _ => None,
+        }
+    }
+}
+
+// one point of the curve: what is left after removing `removed` nodes
+pub struct Step {
+    pub removed: usize,
+    pub largest: usize,
+    pub components: usize,
+}
+
+// every node once, highest score first (ties: smaller id first), in-edges must be built;
+// like WCC, degree and betweenness don't care about edge direction
+pub fn removal_order(
+    graph: &Graph,
+    order: RemovalOrder,
+    samples: usize,
+    seed: Option<u64>,
+    num_threads: usize,
+) -> Vec<usize> {
+    let n = graph.num_nodes;
+    let scores: Vec<f64> = match order {
+        RemovalOrder::Random => {
+            let mut nodes: Vec<usize> = (0..n).collect();
+            nodes.shuffle(&mut seeded_rng(seed));
+            return nodes;
+        }
+        RemovalOrder::Degree => (0..n)
+            .map(|u| (graph.out_degree(u) + graph.in_neighbors(u).len()) as f64)
+            .collect(),
+        RemovalOrder::PageRank => {
+            pagerank_parallel(graph, 0.85, 50, 1e-10, Dangling::Uniform, None, num_threads)
+        }
+        RemovalOrder::Betweenness => betweenness(graph, samples, true, seed, num_threads),
+    };
+
+    let mut nodes: Vec<usize> = (0..n).collect();
+    nodes.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]).then(a.cmp(&b)));
+    nodes
+}
+
+// the same seed gives the same random choices, no seed a different one every run
+fn seeded_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_rng(&mut rand::rng()),
+    }
+}
+
+// Brandes on unweighted edges from `samples` random sources (all nodes if there are
+// fewer), scaled up to the full source count; sources run in parallel.
+// both_directions also follows in-edges (they must be built)
+pub fn betweenness(
+    graph: &Graph,
+    samples: usize,
+    both_directions: bool,
+    seed: Option<u64>,
+    num_threads: usize,
+) -> Vec<f64> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .unwrap()
+        .install(|| betweenness_impl(graph, samples, both_directions, seed))
+}
+
+fn betweenness_impl(
+    graph: &Graph,
+    samples: usize,
+    both_directions: bool,
+    seed: Option<u64>,
+) -> Vec<f64> {
+    let n = graph.num_nodes;
+    let k = samples.clamp(1, n.max(1)).min(n);
+    let sources = index::sample(&mut seeded_rng(seed), n, k).into_vec();
+
+    let mut centrality = sources
+        .par_iter()
+        .fold(
+            || vec![0.0; n],
+            |mut centrality, &s| {
+                add_dependencies(graph, s, both_directions, &mut centrality);
+                centrality
+            },
+        )
+        .reduce(
+            || vec![0.0; n],
+            |mut a, b| {
+                for (x, y) in a.iter_mut().zip(b) {
+                    *x += y;
+                }
+                a
+            },
+        );
+
+    if k > 0 {
+        let scale = n as f64 / k as f64;
+        centrality.par_iter_mut().for_each(|c| *c *= scale);
+    }
+    centrality
+}
+
+// neighbors of a node with every node only once, an edge that exists both ways (or twice)
+// would otherwise count as two shortest paths
+struct Neighbors<'a> {
+    graph: &'a Graph,
+    both_directions: bool,
+    // seen[v] == round when v is already in list
+    seen: Vec<usize>,
+    round: usize,
+    list: Vec<usize>,
+}
+
+impl<'a> Neighbors<'a> {
+    fn new(graph: &'a Graph, both_directions: bool) -> Self {
+        Neighbors {
+            graph,
+            both_directions,
+            seen: vec![usize::MAX; graph.num_nodes],
+            round: 0,
+            list: Vec::new(),
+        }
+    }
+
+    fn of(&mut self, u: usize) -> &[usize] {
+        let in_neighbors = if self.both_directions {
+            self.graph.in_neighbors(u)
+        } else {
+            &[]
+        };
+        self.round += 1;
+        self.list.clear();
+        for &v in self.graph.neighbors(u).iter().chain(in_neighbors) {
+            if self.seen[v] != self.round {
+                self.seen[v] = self.round;
+                self.list.push(v);
+            }
+        }
+        &self.list
+    }
+}
+
+// BFS from s counting shortest paths, then dependencies back from the farthest nodes
+fn add_dependencies(graph: &Graph, s: usize, both_directions: bool, centrality: &mut [f64]) {
+    let n = graph.num_nodes;
+    let mut neighbors = Neighbors::new(graph, both_directions);
+    let mut dist = vec![usize::MAX; n];
+    let mut paths = vec![0.0; n];
+    let mut order = Vec::new();
+    let mut queue = VecDeque::new();
+
+    dist[s] = 0;
+    paths[s] = 1.0;
+    queue.push_back(s);
+    while let Some(u) = queue.pop_front() {
+        order.push(u);
+        for &v in neighbors.of(u) {
+            if dist[v] == usize::MAX {
+                dist[v] = dist[u] + 1;
+                queue.push_back(v);
+            }
+            if dist[v] == dist[u] + 1 {
+                paths[v] += paths[u];
+            }
+        }
+    }
+
+    let mut dependency = vec![0.0; n];
+    for &w in order.iter().rev() {
+        for &v in neighbors.of(w) {
+            if dist[v] == dist[w] + 1 {
+                dependency[w] += paths[w] / paths[v] * (1.0 + dependency[v]);
+            }
+        }
+        if w != s {
+            centrality[w] += dependency[w];
+        }
+    }
+}
+
+// removes the nodes in `order` and measures what is left after every n/steps of them
+pub fn resilience(graph: &Graph, order: &[usize], steps: usize) -> Vec<Step> {
+    let n = order.len();
+    let steps = steps.max(1);
+    let mut removed: Vec<usize> = (0..=steps).map(|i| i * n / steps).collect();
+    removed.dedup();
+
+    removal_curve(graph, order, &removed)
+        .into_iter()
+        .zip(removed)
+        .map(|((largest, components), removed)| Step {
+            removed,
+            largest,
+            components,
+        })
+        .collect()
+}
+
+// mean share of the nodes in the largest component over the steps (1 = nothing breaks)
+pub fn robustness(steps: &[Step], num_nodes: usize) -> f64 {
+    if steps.is_empty() || num_nodes == 0 {
+        return 0.0;
+    }
+    steps
+        .iter()
+        .map(|step| step.largest as f64 / num_nodes as f64)
+        .sum::<f64>()
+        / steps.len() as f64
+}
+
+pub fn write_steps(steps: &[Step], num_nodes: usize, w: &mut dyn Write) -> Result<()> {
+    writeln!(w, "removed fraction largest components")?;
+    for step in steps {
+        let fraction = if num_nodes > 0 {
+            step.removed as f64 / num_nodes as f64
+        } else {
+            0.0
+        };
+        writeln!(
+            w,
+            "{} {:.4} {} {}",
+            step.removed, fraction, step.largest, step.components
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_betweenness_path() {
+        // 0→1→2→3: 1 is on 0→2, 0→3, 2 on 0→3, 1→3
+        let graph = Graph::from_edges(4, &[(0, 1), (1, 2), (2, 3)]);
+        assert_eq!(
+            betweenness(&graph, 4, false, Some(1), 2),
+            vec![0.0, 2.0, 2.0, 0.0]
+        );
+
+        // two equally short paths 0→1→3 and 0→2→3 share the credit
+        let mut graph = Graph::from_edges(4, &[(0, 1), (0, 2), (1, 3), (2, 3)]);
+        assert_eq!(
+            betweenness(&graph, 100, false, Some(1), 2),
+            vec![0.0, 0.5, 0.5, 0.0]
+        );
+
+        // without direction 0 and 3 are also between 1 and 2, every pair is counted both ways
+        graph.build_reverse();
+        assert_eq!(
+            betweenness(&graph, 4, true, Some(1), 2),
+            vec![1.0, 1.0, 1.0, 1.0]
+        );
+
+        // 0↔1 is one edge without direction and 2→3 is doubled, so this is the square above;
+        // with direction 1 and 2 still share 0→3 evenly and 0 is on 1→2
+        let mut graph = Graph::from_edges(4, &[(0, 1), (1, 0), (0, 2), (1, 3), (2, 3), (2, 3)]);
+        graph.build_reverse();
+        assert_eq!(
+            betweenness(&graph, 4, true, Some(1), 2),
+            vec![1.0, 1.0, 1.0, 1.0]
+        );
+        assert_eq!(
+            betweenness(&graph, 4, false, Some(1), 2),
+            vec![1.0, 0.5, 0.5, 0.0]
+        );
+
+        // a sample of the sources is random, but the same for the same seed
+        let graph = Graph::from_edges(50, &(0..49).map(|i| (i, i + 1)).collect::<Vec<_>>());
+        let sampled = betweenness(&graph, 5, false, Some(3), 2);
+        assert_eq!(sampled, betweenness(&graph, 5, false, Some(3), 2));
+        assert!(sampled.iter().any(|&c| c > 0.0));
+    }
+
+    #[test]
+    fn test_resilience_star() {
+        // star around 0 plus the edge 3-4
+        let mut graph = Graph::from_edges(6, &[(0, 1), (0, 2), (0, 3), (0, 4), (0, 5), (3, 4)]);
+        graph.build_reverse();
+
+        let order = removal_order(&graph, RemovalOrder::Degree, 0, None, 2);
+        assert_eq!(order[0], 0);
+        // only the hub is on shortest paths, the rest ties and goes by id
+        let by_betweenness = removal_order(&graph, RemovalOrder::Betweenness, 6, Some(5), 2);
+        assert_eq!(by_betweenness, vec![0, 1, 2, 3, 4, 5]);
+
+        // after the hub only 3-4 stays together
+        let steps = resilience(&graph, &order, 6);
+        let points: Vec<(usize, usize, usize)> = steps
+            .iter()
+            .map(|s| (s.removed, s.largest, s.components))
+            .collect();
+        assert_eq!(points[0], (0, 6, 1));
+        assert_eq!(points[1], (1, 2, 4));
+        assert_eq!(points[6], (6, 0, 0));
+
+        let mut random = removal_order(&graph, RemovalOrder::Random, 0, Some(7), 2);
+        assert_eq!(
+            random,
+            removal_order(&graph, RemovalOrder::Random, 0, Some(7), 2)
+        );
+        random.sort_unstable();
+        assert_eq!(random, (0..6).collect::<Vec<_>>());
+
+        assert!(robustness(&steps, 6) < 0.5);
+    }
+}
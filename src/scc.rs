@@ -0,0 +1,333 @@
+use crate::graph::Graph;
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+const UNVISITED: usize = usize::MAX;
+
+// iterative Tarjan, every node gets the id of its component's root
+pub fn scc_sequential(graph: &Graph) -> Vec<usize> {
+    let n = graph.num_nodes;
+    let mut index = vec![UNVISITED; n];
+    let mut low = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    let mut comp = vec![0; n];
+    let mut counter = 0;
+
+    // (node, position of the next edge to look at)
+    let mut call_stack: Vec<(usize, usize)> = Vec::new();
+
+    for start in 0..n {
+        if index[start] != UNVISITED {
+            continue;
+        }
+
+        index[start] = counter;
+        low[start] = counter;
+        counter += 1;
+        stack.push(start);
+        on_stack[start] = true;
+        call_stack.push((start, 0));
+
+        while let Some((v, next_edge)) = call_stack.last_mut() {
+            let v = *v;
+            let neighbors = graph.neighbors(v);
+
+            if *next_edge < neighbors.len() {
+                let w = neighbors[*next_edge];
+                *next_edge += 1;
+
+                if index[w] == UNVISITED {
+                    index[w] = counter;
+                    low[w] = counter;
+                    counter += 1;
+                    stack.push(w);
+                    on_stack[w] = true;
+                    call_stack.push((w, 0));
+                } else if on_stack[w] {
+                    low[v] = low[v].min(index[w]);
+                }
+                continue;
+            }
+
+            call_stack.pop();
+            if let Some(&(parent, _)) = call_stack.last() {
+                low[parent] = low[parent].min(low[v]);
+            }
+
+            if low[v] == index[v] {
+                while let Some(w) = stack.pop() {
+                    on_stack[w] = false;
+                    comp[w] = v;
+                    if w == v {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    comp
+}
+
+// forward-backward with trimming, graph must have in-edges built (Graph::build_reverse)
+pub fn scc_parallel(graph: &Graph, num_threads: usize) -> Vec<usize> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .unwrap()
+        .install(|| scc_parallel_impl(graph))
+}
+
+// color of nodes whose component is already known
+const DONE: usize = usize::MAX;
+
+fn scc_parallel_impl(graph: &Graph) -> Vec<usize> {
+    assert!(graph.has_reverse(), "parallel SCC needs in-edges");
+
+    let n = graph.num_nodes;
+    let comp: Vec<AtomicUsize> = (0..n).map(AtomicUsize::new).collect();
+    // every open subproblem has its own color, all nodes start in subproblem 0
+    let color: Vec<AtomicUsize> = (0..n).map(|_| AtomicUsize::new(0)).collect();
+    let next_color = AtomicUsize::new(1);
+
+    trim(graph, &color);
+
+    let remaining: Vec<usize> = (0..n)
+        .into_par_iter()
+        .filter(|&v| color[v].load(Ordering::Relaxed) == 0)
+        .collect();
+
+    let mut tasks = if remaining.is_empty() {
+        Vec::new()
+    } else {
+        vec![(0, remaining)]
+    };
+    while !tasks.is_empty() {
+        tasks = tasks
+            .into_par_iter()
+            .flat_map_iter(|(c, nodes)| {
+                forward_backward(graph, &color, &comp, &next_color, c, nodes)
+            })
+            .collect();
+    }
+
+    comp.into_iter().map(AtomicUsize::into_inner).collect()
+}
+
+// nodes without in- or out-edges are singleton components, removing them can expose new ones
+fn trim(graph: &Graph, color: &[AtomicUsize]) {
+    let n = graph.num_nodes;
+    let in_deg: Vec<AtomicUsize> = (0..n)
+        .map(|v| AtomicUsize::new(graph.in_neighbors(v).len()))
+        .collect();
+    let out_deg: Vec<AtomicUsize> = (0..n)
+        .map(|v| AtomicUsize::new(graph.out_degree(v)))
+        .collect();
+    let trimmed: Vec<AtomicBool> = (0..n).map(|_| AtomicBool::new(false)).collect();
+
+    let mut frontier: Vec<usize> = (0..n)
+        .into_par_iter()
+        .filter(|&v| {
+            (graph.out_degree(v) == 0 || graph.in_neighbors(v).is_empty())
+                && !trimmed[v].swap(true, Ordering::Relaxed)
+        })
+        .collect();
+
+    while !frontier.is_empty() {
+        frontier.par_iter().for_each(|&u| {
+            color[u].store(DONE, Ordering::Relaxed);
+        });
+
+        frontier = frontier
+            .par_iter()
+            .flat_map_iter(|&u| {
+                let downstream = graph
+                    .neighbors(u)
+                    .iter()
+                    .filter(|&&v| in_deg[v].fetch_sub(1, Ordering::Relaxed) == 1);
+                let upstream = graph
+                    .in_neighbors(u)
+                    .iter()
+                    .filter(|&&v| out_deg[v].fetch_sub(1, Ordering::Relaxed) == 1);
+
+                downstream
+                    .chain(upstream)
+                    .copied()
+                    .filter(|&v| !trimmed[v].swap(true, Ordering::Relaxed))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+    }
+}
+
+// splits one subproblem into its pivot's component and up to three new subproblems
+fn forward_backward(
+    graph: &Graph,
+    color: &[AtomicUsize],
+    comp: &[AtomicUsize],
+    next_color: &AtomicUsize,
+    c: usize,
+    nodes: Vec<usize>,
+) -> Vec<(usize, Vec<usize>)> {
+    if nodes.len() == 1 {
+        color[nodes[0]].store(DONE, Ordering::Relaxed);
+        return Vec::new();
+    }
+
+    let pivot = nodes[0];
+    let fw = next_color.fetch_add(1, Ordering::Relaxed);
+    let bw = next_color.fetch_add(1, Ordering::Relaxed);
+    let found = next_color.fetch_add(1, Ordering::Relaxed);
+
+    // forward: c -> fw
+    color[pivot].store(fw, Ordering::Relaxed);
+    reach(
+        &[pivot],
+        |v| graph.neighbors(v),
+        |v| claim(&color[v], c, fw),
+    );
+
+    // backward: fw -> found (pivot's component), c -> bw
+    color[pivot].store(found, Ordering::Relaxed);
+    reach(
+        &[pivot],
+        |v| graph.in_neighbors(v),
+        |v| claim(&color[v], fw, found) || claim(&color[v], c, bw),
+    );
+
+    let mut subproblems = vec![Vec::new(), Vec::new(), Vec::new()];
+    for v in nodes {
+        let cv = color[v].load(Ordering::Relaxed);
+        if cv == found {
+            comp[v].store(pivot, Ordering::Relaxed);
+            color[v].store(DONE, Ordering::Relaxed);
+        } else if cv == fw {
+            subproblems[0].push(v);
+        } else if cv == bw {
+            subproblems[1].push(v);
+        } else {
+            subproblems[2].push(v);
+        }
+    }
+
+    [fw, bw, c]
+        .into_iter()
+        .zip(subproblems)
+        .filter(|(_, nodes)| !nodes.is_empty())
+        .collect()
+}
+
+fn claim(color: &AtomicUsize, from: usize, to: usize) -> bool {
+    color
+        .compare_exchange(from, to, Ordering::Relaxed, Ordering::Relaxed)
+        .is_ok()
+}
+
+// level-synchronous traversal, visit decides (atomically) whether a node is entered
+fn reach<'a, N, V>(start: &[usize], next: N, visit: V)
+where
+    N: Fn(usize) -> &'a [usize] + Sync,
+    V: Fn(usize) -> bool + Sync,
+{
+    let mut frontier = start.to_vec();
+
+    while !frontier.is_empty() {
+        frontier = frontier
+            .par_iter()
+            .flat_map_iter(|&u| next(u).iter().copied().filter(|&v| visit(v)))
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    // same grouping of nodes, labels themselves can differ
+    fn same_partition(a: &[usize], b: &[usize]) -> bool {
+        let mut ab = HashMap::new();
+        let mut ba = HashMap::new();
+        a.iter()
+            .zip(b)
+            .all(|(x, y)| *ab.entry(x).or_insert(y) == y && *ba.entry(y).or_insert(x) == x)
+    }
+
+    #[test]
+    fn test_scc_simple() {
+        // 0→1→2→0 cycle, 2→3, 3→4→3 cycle, 5 alone
+        let graph =
+            Graph::from_adjacency(vec![vec![1], vec![2], vec![0, 3], vec![4], vec![3], vec![]]);
+
+        let result = scc_sequential(&graph);
+
+        assert_eq!(result[0], result[1]);
+        assert_eq!(result[1], result[2]);
+        assert_eq!(result[3], result[4]);
+        assert_ne!(result[0], result[3]);
+        assert_ne!(result[5], result[0]);
+        assert_ne!(result[5], result[3]);
+    }
+
+    #[test]
+    fn test_scc_dag() {
+        let graph = Graph::from_adjacency(vec![vec![1, 2], vec![3], vec![3], vec![]]);
+
+        let result = scc_sequential(&graph);
+
+        assert_eq!(result, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_scc_parallel_vs_sequential() {
+        let mut graph = Graph::from_adjacency(vec![
+            vec![1],
+            vec![2],
+            vec![0, 3],
+            vec![4],
+            vec![3, 5],
+            vec![5],
+            vec![0],
+        ]);
+        graph.build_reverse();
+
+        let seq = scc_sequential(&graph);
+        let par = scc_parallel(&graph, 4);
+
+        assert!(same_partition(&seq, &par), "seq={:?} par={:?}", seq, par);
+    }
+
+    #[test]
+    fn test_scc_parallel_line() {
+        // trimming has to peel the whole line, no component larger than one node
+        let adjacency = (0..1000)
+            .map(|i| if i < 999 { vec![i + 1] } else { vec![] })
+            .collect();
+        let mut graph = Graph::from_adjacency(adjacency);
+        graph.build_reverse();
+
+        let par = scc_parallel(&graph, 4);
+
+        assert_eq!(par, (0..1000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_scc_parallel_vs_sequential_random() {
+        use crate::formats::GraphFormat;
+        use crate::graph_generator::generate_random_parallel;
+
+        let path = "test_scc_parallel.txt";
+        generate_random_parallel(2000, 3000, path, GraphFormat::EdgeList, false).unwrap();
+
+        let mut graph = Graph::from_file(path).unwrap();
+        graph.build_reverse();
+
+        let seq = scc_sequential(&graph);
+        let par = scc_parallel(&graph, 4);
+
+        assert!(same_partition(&seq, &par));
+
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,220 @@
+use crate::graph::Graph;
+use rayon::prelude::*;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// distances are never negative (the loader rejects negative weights), and for
+// non-negative f64 the bit patterns compare the same way as the numbers, so
+// distances can go through u64 heaps and AtomicU64::fetch_min directly
+
+pub fn dijkstra(graph: &Graph, source: usize) -> Vec<f64> {
+    let mut dist = vec![f64::INFINITY; graph.num_nodes];
+    let mut heap = BinaryHeap::new();
+
+    dist[source] = 0.0;
+    heap.push(Reverse((0.0f64.to_bits(), source)));
+
+    while let Some(Reverse((bits, node))) = heap.pop() {
+        let d = f64::from_bits(bits);
+        if d > dist[node] {
+            continue; // stale entry
+        }
+
+        for (neighbor, weight) in graph.weighted_neighbors(node) {
+            let candidate = d + weight;
+            if candidate < dist[neighbor] {
+                dist[neighbor] = candidate;
+                heap.push(Reverse((candidate.to_bits(), neighbor)));
+            }
+        }
+    }
+
+    dist
+}
+
+pub fn delta_stepping(graph: &Graph, source: usize, delta: f64, num_threads: usize) -> Vec<f64> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .unwrap()
+        .install(|| delta_stepping_impl(graph, source, delta))
+}
+
+// mean edge weight, one bucket per BFS level on unweighted graphs
+pub fn default_delta(graph: &Graph) -> f64 {
+    let m = graph.num_edges();
+    if m == 0 {
+        return 1.0;
+    }
+
+    let total: f64 = (0..graph.num_nodes)
+        .into_par_iter()
+        .map(|u| graph.out_weight(u))
+        .sum();
+    let mean = total / m as f64;
+
+    if mean > 0.0 && mean.is_finite() {
+        mean
+    } else {
+        1.0
+    }
+}
+
+fn delta_stepping_impl(graph: &Graph, source: usize, delta: f64) -> Vec<f64> {
+    let dist: Vec<AtomicU64> = (0..graph.num_nodes)
+        .map(|_| AtomicU64::new(f64::INFINITY.to_bits()))
+        .collect();
+    dist[source].store(0.0f64.to_bits(), Ordering::Relaxed);
+
+    // only non-empty buckets are kept, a tiny delta or a heavy edge gives huge bucket numbers
+    let bucket_of = |d: f64| (d / delta) as usize;
+    let mut buckets: BTreeMap<usize, Vec<usize>> = BTreeMap::from([(0, vec![source])]);
+
+    while let Some(&current) = buckets.keys().next() {
+        let mut settled = Vec::new();
+
+        // light edges can put nodes back into the current bucket, repeat until it stays empty
+        while let Some(mut frontier) = buckets.remove(&current) {
+            frontier.par_sort_unstable();
+            frontier.dedup();
+            frontier.retain(|&u| bucket_of(load(&dist[u])) == current);
+
+            let improved = relax(graph, &dist, &frontier, |w| w <= delta);
+            insert(&mut buckets, &dist, improved, bucket_of);
+            settled.extend(frontier);
+        }
+
+        let improved = relax(graph, &dist, &settled, |w| w > delta);
+        insert(&mut buckets, &dist, improved, bucket_of);
+    }
+
+    dist.iter().map(load).collect()
+}
+
+#[inline]
+fn load(d: &AtomicU64) -> f64 {
+    f64::from_bits(d.load(Ordering::Relaxed))
+}
+
+// relaxes the selected edges of every node in parallel, returns nodes whose distance dropped
+fn relax<F>(graph: &Graph, dist: &[AtomicU64], nodes: &[usize], select: F) -> Vec<usize>
+where
+    F: Fn(f64) -> bool + Sync,
+{
+    nodes
+        .par_iter()
+        .flat_map_iter(|&u| {
+            let du = load(&dist[u]);
+            graph
+                .weighted_neighbors(u)
+                .filter(|&(_, w)| select(w))
+                .filter_map(move |(v, w)| {
+                    let candidate = (du + w).to_bits();
+                    let old = dist[v].fetch_min(candidate, Ordering::Relaxed);
+                    (candidate < old).then_some(v)
+                })
+        })
+        .collect()
+}
+
+fn insert<B>(
+    buckets: &mut BTreeMap<usize, Vec<usize>>,
+    dist: &[AtomicU64],
+    nodes: Vec<usize>,
+    bucket_of: B,
+) where
+    B: Fn(f64) -> usize,
+{
+    for v in nodes {
+        buckets
+            .entry(bucket_of(load(&dist[v])))
+            .or_default()
+            .push(v);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dijkstra_simple() {
+        // direct edge 0→2 is more expensive than going through 1
+        let graph =
+            Graph::from_weighted_edges(4, &[(0, 1, 1.0), (0, 2, 5.0), (1, 2, 1.5), (2, 3, 0.5)]);
+
+        let dist = dijkstra(&graph, 0);
+
+        assert_eq!(dist, vec![0.0, 1.0, 2.5, 3.0]);
+    }
+
+    #[test]
+    fn test_dijkstra_unreachable() {
+        let graph = Graph::from_weighted_edges(3, &[(1, 0, 1.0)]);
+
+        let dist = dijkstra(&graph, 0);
+
+        assert_eq!(dist[0], 0.0);
+        assert!(dist[1].is_infinite());
+        assert!(dist[2].is_infinite());
+    }
+
+    #[test]
+    fn test_dijkstra_unweighted_matches_bfs() {
+        use crate::bfs::bfs_sequential;
+
+        let graph = Graph::from_adjacency(vec![vec![1, 2], vec![3], vec![3], vec![4], vec![]]);
+
+        let dist = dijkstra(&graph, 0);
+        let hops = bfs_sequential(&graph, 0);
+
+        for i in 0..5 {
+            assert_eq!(dist[i], hops[i] as f64);
+        }
+    }
+
+    #[test]
+    fn test_delta_stepping_vs_dijkstra() {
+        let graph =
+            Graph::from_weighted_edges(4, &[(0, 1, 1.0), (0, 2, 5.0), (1, 2, 1.5), (2, 3, 0.5)]);
+
+        for delta in [1e-9, 0.1, 1.0, 10.0] {
+            assert_eq!(dijkstra(&graph, 0), delta_stepping(&graph, 0, delta, 4));
+        }
+
+        // a bucket per delta would mean ~1e15 of them here
+        let graph = Graph::from_weighted_edges(3, &[(0, 1, 1e12), (1, 2, 0.5)]);
+        assert_eq!(dijkstra(&graph, 0), delta_stepping(&graph, 0, 1e-3, 4));
+    }
+
+    #[test]
+    fn test_delta_stepping_random() {
+        use rand::Rng;
+
+        let mut rng = rand::rng();
+        let edges: Vec<(usize, usize, f64)> = (0..5000)
+            .map(|_| {
+                (
+                    rng.random_range(0..1000),
+                    rng.random_range(0..1000),
+                    rng.random_range(0.0..10.0),
+                )
+            })
+            .collect();
+        let graph = Graph::from_weighted_edges(1000, &edges);
+
+        let seq = dijkstra(&graph, 0);
+        let par = delta_stepping(&graph, 0, default_delta(&graph), 4);
+
+        for i in 0..1000 {
+            assert!(
+                seq[i] == par[i] || (seq[i] - par[i]).abs() < 1e-9,
+                "Node {}: seq={}, par={}",
+                i,
+                seq[i],
+                par[i]
+            );
+        }
+    }
+}
@@ -0,0 +1,310 @@
+use crate::graph::Graph;
+use rayon::prelude::*;
+use std::fs::File;
+use std::io::{BufWriter, Result, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub struct DegreeStats {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    pub median: f64,
+}
+
+// nodes with degree in low..=high, bin 0 is degree 0, then [1], [2, 3], [4, 7], ...
+pub struct Bin {
+    pub low: usize,
+    pub high: usize,
+    pub out_nodes: usize,
+    pub in_nodes: usize,
+}
+
+pub struct GraphStats {
+    pub nodes: usize,
+    pub edges: usize,
+    // edges / n(n - 1)
+    pub density: f64,
+    pub out_degree: DegreeStats,
+    pub in_degree: DegreeStats,
+    pub histogram: Vec<Bin>,
+    pub self_loops: usize,
+    // extra copies of an edge that is already there
+    pub duplicates: usize,
+    // only out-edges / only in-edges / no edges at all
+    pub sources: usize,
+    pub sinks: usize,
+    pub isolated: usize,
+    // share of distinct u→v (u != v) that also have v→u
+    pub reciprocity: f64,
+}
+
+pub fn graph_stats(graph: &Graph, num_threads: usize) -> GraphStats {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .unwrap()
+        .install(|| graph_stats_impl(graph))
+}
+
+fn graph_stats_impl(graph: &Graph) -> GraphStats {
+    let n = graph.num_nodes;
+
+    let in_count: Vec<AtomicUsize> = (0..n).map(|_| AtomicUsize::new(0)).collect();
+    graph.csr().1.par_iter().for_each(|&v| {
+        in_count[v].fetch_add(1, Ordering::Relaxed);
+    });
+    let in_degrees: Vec<usize> = in_count.into_iter().map(AtomicUsize::into_inner).collect();
+    let out_degrees: Vec<usize> = (0..n)
+        .into_par_iter()
+        .map(|u| graph.out_degree(u))
+        .collect();
+
+    // distinct targets of every node
+    let lists: Vec<Vec<usize>> = (0..n)
+        .into_par_iter()
+        .map(|u| {
+            let mut list = graph.neighbors(u).to_vec();
+            list.sort_unstable();
+            list.dedup();
+            list
+        })
+        .collect();
+    let self_loops: usize = (0..n)
+        .into_par_iter()
+        .map(|u| graph.neighbors(u).iter().filter(|&&v| v == u).count())
+        .sum();
+    let duplicates = graph.num_edges() - lists.par_iter().map(Vec::len).sum::<usize>();
+
+    let (distinct, reciprocal) = (0..n)
+        .into_par_iter()
+        .map(|u| {
+            let mut distinct = 0;
+            let mut reciprocal = 0;
+            for &v in lists[u].iter().filter(|&&v| v != u) {
+                distinct += 1;
+                if lists[v].binary_search(&u).is_ok() {
+                    reciprocal += 1;
+                }
+            }
+            (distinct, reciprocal)
+        })
+        .reduce(|| (0, 0), |a, b| (a.0 + b.0, a.1 + b.1));
+
+    let (sources, sinks, isolated) = (0..n)
+        .into_par_iter()
+        .map(|u| match (out_degrees[u] > 0, in_degrees[u] > 0) {
+            (true, false) => (1, 0, 0),
+            (false, true) => (0, 1, 0),
+            (false, false) => (0, 0, 1),
+            (true, true) => (0, 0, 0),
+        })
+        .reduce(|| (0, 0, 0), |a, b| (a.0 + b.0, a.1 + b.1, a.2 + b.2));
+
+    let edges = graph.num_edges();
+    GraphStats {
+        nodes: n,
+        edges,
+        density: if n > 1 {
+            edges as f64 / (n as f64 * (n - 1) as f64)
+        } else {
+            0.0
+        },
+        histogram: histogram(&out_degrees, &in_degrees),
+        out_degree: degree_stats(out_degrees),
+        in_degree: degree_stats(in_degrees),
+        self_loops,
+        duplicates,
+        sources,
+        sinks,
+        isolated,
+        reciprocity: if distinct > 0 {
+            reciprocal as f64 / distinct as f64
+        } else {
+            0.0
+        },
+    }
+}
+
+fn degree_stats(mut degrees: Vec<usize>) -> DegreeStats {
+    if degrees.is_empty() {
+        return DegreeStats {
+            min: 0,
+            max: 0,
+            mean: 0.0,
+            median: 0.0,
+        };
+    }
+
+    degrees.par_sort_unstable();
+    let len = degrees.len();
+    let median = if len % 2 == 1 {
+        degrees[len / 2] as f64
+    } else {
+        (degrees[len / 2 - 1] + degrees[len / 2]) as f64 / 2.0
+    };
+
+    DegreeStats {
+        min: degrees[0],
+        max: degrees[len - 1],
+        mean: degrees.par_iter().sum::<usize>() as f64 / len as f64,
+        median,
+    }
+}
+
+// 0 for degree 0, k for degrees 2^(k-1)..2^k - 1
+fn bin_of(degree: usize) -> usize {
+    (usize::BITS - degree.leading_zeros()) as usize
+}
+
+fn histogram(out_degrees: &[usize], in_degrees: &[usize]) -> Vec<Bin> {
+    let count = |degrees: &[usize]| {
+        degrees
+            .par_iter()
+            .fold(Vec::new, |mut bins: Vec<usize>, &d| {
+                let b = bin_of(d);
+                if bins.len() <= b {
+                    bins.resize(b + 1, 0);
+                }
+                bins[b] += 1;
+                bins
+            })
+            .reduce(Vec::new, |mut a, b| {
+                if a.len() < b.len() {
+                    a.resize(b.len(), 0);
+                }
+                for (x, y) in a.iter_mut().zip(b) {
+                    *x += y;
+                }
+                a
+            })
+    };
+    let out_bins = count(out_degrees);
+    let in_bins = count(in_degrees);
+
+    (0..out_bins.len().max(in_bins.len()))
+        .map(|b| Bin {
+            low: if b == 0 { 0 } else { 1 << (b - 1) },
+            high: if b == 0 { 0 } else { (1 << b) - 1 },
+            out_nodes: out_bins.get(b).copied().unwrap_or(0),
+            in_nodes: in_bins.get(b).copied().unwrap_or(0),
+        })
+        .collect()
+}
+
+pub fn save_stats(stats: &GraphStats, path: &str, json: bool) -> Result<()> {
+    let mut w = BufWriter::new(File::create(path)?);
+    if json {
+        write_stats_json(stats, &mut w)?;
+    } else {
+        write_stats_text(stats, &mut w)?;
+    }
+    w.flush()
+}
+
+pub fn write_stats_text(stats: &GraphStats, w: &mut dyn Write) -> Result<()> {
+    writeln!(w, "Nodes: {}", stats.nodes)?;
+    writeln!(w, "Edges: {}", stats.edges)?;
+    writeln!(w, "Density: {:.6e}", stats.density)?;
+    for (name, d) in [("Out", &stats.out_degree), ("In", &stats.in_degree)] {
+        writeln!(
+            w,
+            "{}-degree: min {}, max {}, mean {:.3}, median {}",
+            name, d.min, d.max, d.mean, d.median
+        )?;
+    }
+    writeln!(w, "Self-loops: {}", stats.self_loops)?;
+    writeln!(w, "Duplicate edges: {}", stats.duplicates)?;
+    writeln!(
+        w,
+        "Sources: {}, sinks: {}, isolated: {}",
+        stats.sources, stats.sinks, stats.isolated
+    )?;
+    writeln!(w, "Reciprocity: {:.4}", stats.reciprocity)?;
+
+    writeln!(w, "Degree histogram (degree: out-nodes in-nodes):")?;
+    for bin in &stats.histogram {
+        let range = if bin.low == bin.high {
+            bin.low.to_string()
+        } else {
+            format!("{}-{}", bin.low, bin.high)
+        };
+        writeln!(w, "   {:>13}: {} {}", range, bin.out_nodes, bin.in_nodes)?;
+    }
+    Ok(())
+}
+
+fn write_stats_json(stats: &GraphStats, w: &mut dyn Write) -> Result<()> {
+    let degree = |d: &DegreeStats| {
+        format!(
+            "{{\"min\": {}, \"max\": {}, \"mean\": {}, \"median\": {}}}",
+            d.min, d.max, d.mean, d.median
+        )
+    };
+    let bins: Vec<String> = stats
+        .histogram
+        .iter()
+        .map(|b| {
+            format!(
+                "{{\"low\": {}, \"high\": {}, \"out_nodes\": {}, \"in_nodes\": {}}}",
+                b.low, b.high, b.out_nodes, b.in_nodes
+            )
+        })
+        .collect();
+
+    writeln!(w, "{{")?;
+    writeln!(w, "  \"nodes\": {},", stats.nodes)?;
+    writeln!(w, "  \"edges\": {},", stats.edges)?;
+    writeln!(w, "  \"density\": {},", stats.density)?;
+    writeln!(w, "  \"out_degree\": {},", degree(&stats.out_degree))?;
+    writeln!(w, "  \"in_degree\": {},", degree(&stats.in_degree))?;
+    writeln!(w, "  \"self_loops\": {},", stats.self_loops)?;
+    writeln!(w, "  \"duplicates\": {},", stats.duplicates)?;
+    writeln!(w, "  \"sources\": {},", stats.sources)?;
+    writeln!(w, "  \"sinks\": {},", stats.sinks)?;
+    writeln!(w, "  \"isolated\": {},", stats.isolated)?;
+    writeln!(w, "  \"reciprocity\": {},", stats.reciprocity)?;
+    writeln!(w, "  \"histogram\": [\n    {}\n  ]", bins.join(",\n    "))?;
+    writeln!(w, "}}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_stats() {
+        // 0↔1, 1→2 twice, 2→2, 3 isolated, 4→0
+        let graph = Graph::from_edges(5, &[(0, 1), (1, 0), (1, 2), (1, 2), (2, 2), (4, 0)]);
+        let stats = graph_stats(&graph, 4);
+
+        assert_eq!(stats.nodes, 5);
+        assert_eq!(stats.edges, 6);
+        assert!((stats.density - 0.3).abs() < 1e-12);
+        assert_eq!(stats.self_loops, 1);
+        assert_eq!(stats.duplicates, 1);
+        assert_eq!((stats.sources, stats.sinks, stats.isolated), (1, 0, 1));
+        // distinct non-loop edges 0→1, 1→0, 1→2, 4→0
+        assert!((stats.reciprocity - 0.5).abs() < 1e-12);
+
+        // out-degrees 1, 3, 1, 0, 1
+        assert_eq!(stats.out_degree.min, 0);
+        assert_eq!(stats.out_degree.max, 3);
+        assert_eq!(stats.out_degree.median, 1.0);
+        assert!((stats.out_degree.mean - 1.2).abs() < 1e-12);
+        // in-degrees 2, 1, 3, 0, 0
+        assert_eq!(stats.in_degree.median, 1.0);
+
+        let bins: Vec<(usize, usize, usize, usize)> = stats
+            .histogram
+            .iter()
+            .map(|b| (b.low, b.high, b.out_nodes, b.in_nodes))
+            .collect();
+        assert_eq!(bins, vec![(0, 0, 1, 2), (1, 1, 3, 1), (2, 3, 1, 2)]);
+    }
+
+    #[test]
+    fn test_bin_of() {
+        let bins: Vec<usize> = [0, 1, 2, 3, 4, 7, 8, 1000].map(bin_of).to_vec();
+        assert_eq!(bins, vec![0, 1, 2, 2, 3, 3, 4, 10]);
+    }
+}
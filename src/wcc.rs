@@ -1,10 +1,14 @@
 use crate::graph::Graph;
+use rand::Rng;
 use rayon::prelude::*;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
 struct UnionFind {
     parent: Vec<usize>,
     rank: Vec<usize>,
+    // number of nodes in the set, only kept up to date for roots
+    size: Vec<usize>,
 }
 
 impl UnionFind {
@@ -12,6 +16,7 @@ impl UnionFind {
         UnionFind {
             parent: (0..size).collect(),
             rank: vec![0; size],
+            size: vec![1; size],
         }
     }
 
@@ -22,21 +27,32 @@ impl UnionFind {
         self.parent[x]
     }
 
-    fn union(&mut self, x: usize, y: usize) {
+    // false if x and y were already in the same set
+    fn union(&mut self, x: usize, y: usize) -> bool {
         let root_x = self.find(x);
         let root_y = self.find(y);
 
-        if root_x != root_y {
-            // Union by rank
-            if self.rank[root_x] < self.rank[root_y] {
-                self.parent[root_x] = root_y;
-            } else if self.rank[root_x] > self.rank[root_y] {
-                self.parent[root_y] = root_x;
-            } else {
-                self.parent[root_y] = root_x;
-                self.rank[root_x] += 1;
-            }
+        if root_x == root_y {
+            return false;
+        }
+
+        // Union by rank
+        let (child, root) = if self.rank[root_x] < self.rank[root_y] {
+            (root_x, root_y)
+        } else {
+            (root_y, root_x)
+        };
+        if self.rank[root_x] == self.rank[root_y] {
+            self.rank[root] += 1;
         }
+        self.parent[child] = root;
+        self.size[root] += self.size[child];
+        true
+    }
+
+    fn set_size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
     }
 }
 
@@ -45,7 +61,7 @@ pub fn wcc_sequential(graph: &Graph) -> Vec<usize> {
 
     // make union for every edge
     for u in 0..graph.num_nodes {
-        for &v in &graph.edges[u] {
+        for &v in graph.neighbors(u) {
             uf.union(u, v);
         }
     }
@@ -61,7 +77,7 @@ struct ConcurrentUnionFind {
 impl ConcurrentUnionFind {
     fn new(size: usize) -> Self {
         ConcurrentUnionFind {
-            parent: (0..size).map(|i| AtomicUsize::new(i)).collect(),
+            parent: (0..size).map(AtomicUsize::new).collect(),
         }
     }
 
@@ -86,13 +102,22 @@ impl ConcurrentUnionFind {
         }
     }
 
-    fn union(&self, x: usize, y: usize) {
+    // every node points straight at its root, only while nothing else changes the forest
+    fn compress(&self) {
+        (0..self.parent.len()).into_par_iter().for_each(|x| {
+            let root = self.find(x);
+            self.parent[x].store(root, Ordering::Release);
+        });
+    }
+
+    // the root that was hung under the other one, None if x and y were already together
+    fn union(&self, x: usize, y: usize) -> Option<usize> {
         loop {
             let root_x = self.find(x);
             let root_y = self.find(y);
 
             if root_x == root_y {
-                return;
+                return None;
             }
 
             let (smaller, larger) = if root_x < root_y {
@@ -107,7 +132,7 @@ impl ConcurrentUnionFind {
                 Ordering::Release,
                 Ordering::Acquire,
             ) {
-                Ok(_) => return,
+                Ok(_) => return Some(larger),
                 Err(_) => continue,
             }
         }
@@ -126,7 +151,7 @@ fn wcc_parallel_impl(graph: &Graph) -> Vec<usize> {
     let uf = ConcurrentUnionFind::new(graph.num_nodes);
 
     (0..graph.num_nodes).into_par_iter().for_each(|u| {
-        for &v in &graph.edges[u] {
+        for &v in graph.neighbors(u) {
             uf.union(u, v);
         }
     });
@@ -137,6 +162,415 @@ fn wcc_parallel_impl(graph: &Graph) -> Vec<usize> {
         .collect()
 }
 
+// (largest component, number of components) of the graph without the nodes order[..k],
+// for every k of `removed` (ascending, at most order.len()); order has every node once.
+// the nodes are added back from the end of the order, so the whole curve is one union-find pass,
+// in-edges are used too when the graph has them (otherwise it should be symmetric)
+pub fn removal_curve(graph: &Graph, order: &[usize], removed: &[usize]) -> Vec<(usize, usize)> {
+    let mut uf = UnionFind::new(graph.num_nodes);
+    let mut present = vec![false; graph.num_nodes];
+    let mut largest = 0;
+    let mut components = 0;
+    let mut curve = vec![(0, 0); removed.len()];
+    let mut next = removed.len();
+
+    for k in (0..=order.len()).rev() {
+        while next > 0 && removed[next - 1] == k {
+            next -= 1;
+            curve[next] = (largest, components);
+        }
+        if k == 0 {
+            break;
+        }
+
+        let u = order[k - 1];
+        present[u] = true;
+        components += 1;
+        largest = largest.max(1);
+        let in_neighbors = if graph.has_reverse() {
+            graph.in_neighbors(u)
+        } else {
+            &[]
+        };
+        for &v in graph.neighbors(u).iter().chain(in_neighbors) {
+            if present[v] && uf.union(u, v) {
+                components -= 1;
+                largest = largest.max(uf.set_size(u));
+            }
+        }
+    }
+
+    curve
+}
+
+// components that only grow: edges arrive in batches (applied in parallel),
+// queries are answered between the batches without recomputing anything
+pub struct Connectivity {
+    uf: ConcurrentUnionFind,
+    // number of nodes in the component, only kept up to date for roots
+    size: Vec<usize>,
+    components: usize,
+    pool: rayon::ThreadPool,
+}
+
+impl Connectivity {
+    pub fn new(num_nodes: usize, num_threads: usize) -> Self {
+        Connectivity {
+            uf: ConcurrentUnionFind::new(num_nodes),
+            size: vec![1; num_nodes],
+            components: num_nodes,
+            pool: rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+                .unwrap(),
+        }
+    }
+
+    pub fn from_graph(graph: &Graph, num_threads: usize) -> Self {
+        let mut connectivity = Connectivity::new(graph.num_nodes, num_threads);
+        connectivity.link(|uf| {
+            (0..graph.num_nodes)
+                .into_par_iter()
+                .flat_map_iter(|u| {
+                    graph
+                        .neighbors(u)
+                        .iter()
+                        .filter_map(move |&v| uf.union(u, v))
+                })
+                .collect()
+        });
+        connectivity
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.size.len()
+    }
+
+    pub fn num_components(&self) -> usize {
+        self.components
+    }
+
+    // new nodes start alone, ids num_nodes()..num_nodes
+    pub fn grow(&mut self, num_nodes: usize) {
+        for u in self.num_nodes()..num_nodes {
+            self.uf.parent.push(AtomicUsize::new(u));
+            self.size.push(1);
+            self.components += 1;
+        }
+    }
+
+    // nodes beyond num_nodes() are added
+    pub fn add_edges(&mut self, edges: &[(usize, usize)]) {
+        let max = edges.iter().map(|&(u, v)| u.max(v) + 1).max().unwrap_or(0);
+        self.grow(max);
+        self.link(|uf| {
+            edges
+                .par_iter()
+                .filter_map(|&(u, v)| uf.union(u, v))
+                .collect()
+        });
+    }
+
+    // runs the unions on the pool, then moves the sizes of the roots they hung
+    // under other roots: those were roots before, so their sizes are still the old ones
+    fn link<F>(&mut self, unions: F)
+    where
+        F: Fn(&ConcurrentUnionFind) -> Vec<usize> + Sync,
+    {
+        let uf = &self.uf;
+        let merged = self.pool.install(|| unions(uf));
+        for &root in &merged {
+            let new_root = self.uf.find(root);
+            self.size[new_root] += self.size[root];
+        }
+        self.components -= merged.len();
+    }
+
+    pub fn connected(&self, u: usize, v: usize) -> bool {
+        self.uf.find(u) == self.uf.find(v)
+    }
+
+    pub fn component_size(&self, u: usize) -> usize {
+        self.size[self.uf.find(u)]
+    }
+}
+
+// links along this many out-edges of every node before the giant component is sampled
+const AFFOREST_ROUNDS: usize = 2;
+const AFFOREST_SAMPLES: usize = 1024;
+
+// Afforest (Sutton et al.): the union-find above, but after a few rounds of single edges
+// the nodes already in the biggest component skip the rest of their edges,
+// graph needs in-edges (Graph::build_reverse) so edges into a skipped node are still seen
+pub fn wcc_afforest(graph: &Graph, num_threads: usize) -> Vec<usize> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .unwrap()
+        .install(|| wcc_afforest_impl(graph))
+}
+
+fn wcc_afforest_impl(graph: &Graph) -> Vec<usize> {
+    assert!(graph.has_reverse(), "Afforest needs in-edges");
+
+    let n = graph.num_nodes;
+    let uf = ConcurrentUnionFind::new(n);
+
+    for round in 0..AFFOREST_ROUNDS {
+        (0..n).into_par_iter().for_each(|u| {
+            if let Some(&v) = graph.neighbors(u).get(round) {
+                uf.union(u, v);
+            }
+        });
+    }
+
+    // after compress the giant's nodes point at its root, and they keep pointing there
+    // when that root is later hung under a smaller one (the live root would change)
+    uf.compress();
+    let giant = most_frequent_root(&uf, n);
+    (0..n).into_par_iter().for_each(|u| {
+        if Some(uf.parent[u].load(Ordering::Acquire)) == giant {
+            return;
+        }
+        for &v in graph.neighbors(u).iter().skip(AFFOREST_ROUNDS) {
+            uf.union(u, v);
+        }
+        for &v in graph.in_neighbors(u) {
+            uf.union(u, v);
+        }
+    });
+
+    (0..n).into_par_iter().map(|i| uf.find(i)).collect()
+}
+
+// root of the biggest component among randomly sampled nodes
+fn most_frequent_root(uf: &ConcurrentUnionFind, n: usize) -> Option<usize> {
+    if n == 0 {
+        return None;
+    }
+    let mut rng = rand::rng();
+    let mut counts: HashMap<usize, usize> = HashMap::new();
+    for _ in 0..AFFOREST_SAMPLES {
+        *counts.entry(uf.find(rng.random_range(0..n))).or_insert(0) += 1;
+    }
+    counts
+        .into_iter()
+        .max_by_key(|&(root, count)| (count, std::cmp::Reverse(root)))
+        .map(|(root, _)| root)
+}
+
+// Shiloach-Vishkin: every round hangs roots under the smaller label across an edge (hooking),
+// then points every node straight at its root (shortcutting), until no root moves
+pub fn wcc_shiloach_vishkin(graph: &Graph, num_threads: usize) -> Vec<usize> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .unwrap()
+        .install(|| wcc_shiloach_vishkin_impl(graph))
+}
+
+fn wcc_shiloach_vishkin_impl(graph: &Graph) -> Vec<usize> {
+    let n = graph.num_nodes;
+    let comp: Vec<AtomicUsize> = (0..n).map(AtomicUsize::new).collect();
+
+    loop {
+        let changed = AtomicBool::new(false);
+        (0..n).into_par_iter().for_each(|u| {
+            for &v in graph.neighbors(u) {
+                let comp_u = comp[u].load(Ordering::Relaxed);
+                let comp_v = comp[v].load(Ordering::Relaxed);
+                if comp_u == comp_v {
+                    continue;
+                }
+                let (high, low) = if comp_u > comp_v {
+                    (comp_u, comp_v)
+                } else {
+                    (comp_v, comp_u)
+                };
+                // labels only get smaller, so the pointers can't form a cycle
+                if comp[high].load(Ordering::Relaxed) == high {
+                    comp[high].fetch_min(low, Ordering::Relaxed);
+                    changed.store(true, Ordering::Relaxed);
+                }
+            }
+        });
+
+        (0..n).into_par_iter().for_each(|u| {
+            loop {
+                let parent = comp[u].load(Ordering::Relaxed);
+                let grandparent = comp[parent].load(Ordering::Relaxed);
+                if parent == grandparent {
+                    break;
+                }
+                comp[u].store(grandparent, Ordering::Relaxed);
+            }
+        });
+
+        if !changed.load(Ordering::Relaxed) {
+            break;
+        }
+    }
+
+    comp.into_iter().map(AtomicUsize::into_inner).collect()
+}
+
+// min-label propagation: every edge pulls both ends to the smaller label until nothing changes,
+// needs as many rounds as the longest shortest path, labels are the smallest node of the component
+pub fn wcc_label_propagation(graph: &Graph, num_threads: usize) -> Vec<usize> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .unwrap()
+        .install(|| wcc_label_propagation_impl(graph))
+}
+
+fn wcc_label_propagation_impl(graph: &Graph) -> Vec<usize> {
+    let n = graph.num_nodes;
+    let labels: Vec<AtomicUsize> = (0..n).map(AtomicUsize::new).collect();
+
+    loop {
+        let changed = AtomicBool::new(false);
+        (0..n).into_par_iter().for_each(|u| {
+            for &v in graph.neighbors(u) {
+                let label_u = labels[u].load(Ordering::Relaxed);
+                let label_v = labels[v].load(Ordering::Relaxed);
+                if label_u < label_v {
+                    labels[v].fetch_min(label_u, Ordering::Relaxed);
+                    changed.store(true, Ordering::Relaxed);
+                } else if label_v < label_u {
+                    labels[u].fetch_min(label_v, Ordering::Relaxed);
+                    changed.store(true, Ordering::Relaxed);
+                }
+            }
+        });
+
+        if !changed.load(Ordering::Relaxed) {
+            break;
+        }
+    }
+
+    labels.into_iter().map(AtomicUsize::into_inner).collect()
+}
+
+// union-find roots depend on the order of unions, so they differ between runs and modes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Labeling {
+    // whatever the algorithm returned
+    Root,
+    // smallest node id in the component
+    Min,
+    // 0..k, biggest component first (ties: smaller smallest node first)
+    Dense,
+}
+
+impl Labeling {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "root" => Some(Labeling::Root),
+            "min" => Some(Labeling::Min),
+            "dense" => Some(Labeling::Dense),
+            _ => None,
+        }
+    }
+}
+
+// same components, labels chosen by `labeling`, works for any labeling of the nodes
+pub fn canonical_labels(labels: &[usize], labeling: Labeling) -> Vec<usize> {
+    if labeling == Labeling::Root {
+        return labels.to_vec();
+    }
+
+    let smallest: Vec<AtomicUsize> = (0..labels.len())
+        .map(|_| AtomicUsize::new(usize::MAX))
+        .collect();
+    labels.par_iter().enumerate().for_each(|(u, &label)| {
+        smallest[label].fetch_min(u, Ordering::Relaxed);
+    });
+    let min_labels: Vec<usize> = labels
+        .par_iter()
+        .map(|&label| smallest[label].load(Ordering::Relaxed))
+        .collect();
+    if labeling == Labeling::Min {
+        return min_labels;
+    }
+
+    let sizes = component_sizes(&min_labels);
+    let mut rank = vec![0; labels.len()];
+    for (i, &(_, first)) in sizes.iter().enumerate() {
+        rank[first] = i;
+    }
+    min_labels.par_iter().map(|&first| rank[first]).collect()
+}
+
+// (size, smallest node) of every component, biggest first, labels must be Labeling::Min
+fn component_sizes(min_labels: &[usize]) -> Vec<(usize, usize)> {
+    let counts: Vec<AtomicUsize> = (0..min_labels.len()).map(|_| AtomicUsize::new(0)).collect();
+    min_labels.par_iter().for_each(|&first| {
+        counts[first].fetch_add(1, Ordering::Relaxed);
+    });
+
+    let mut sizes: Vec<(usize, usize)> = (0..min_labels.len())
+        .into_par_iter()
+        .filter(|&u| min_labels[u] == u)
+        .map(|u| (counts[u].load(Ordering::Relaxed), u))
+        .collect();
+    sizes.par_sort_unstable_by_key(|&(size, first)| (std::cmp::Reverse(size), first));
+    sizes
+}
+
+pub struct ComponentSummary {
+    pub count: usize,
+    pub largest: usize,
+    // largest / number of nodes
+    pub largest_fraction: f64,
+    // (component size, how many components have it), biggest size first
+    pub distribution: Vec<(usize, usize)>,
+    // members of the biggest components in ascending order, biggest first
+    pub top: Vec<Vec<usize>>,
+}
+
+// labels can come from any WCC function, `top` is how many components to list
+pub fn component_summary(labels: &[usize], top: usize) -> ComponentSummary {
+    let min_labels = canonical_labels(labels, Labeling::Min);
+    let sizes = component_sizes(&min_labels);
+
+    let mut distribution: Vec<(usize, usize)> = Vec::new();
+    for &(size, _) in &sizes {
+        match distribution.last_mut() {
+            Some((last, count)) if *last == size => *count += 1,
+            _ => distribution.push((size, 1)),
+        }
+    }
+
+    let mut slot = vec![usize::MAX; labels.len()];
+    for (i, &(_, first)) in sizes.iter().take(top).enumerate() {
+        slot[first] = i;
+    }
+    let mut members: Vec<Vec<usize>> = sizes
+        .iter()
+        .take(top)
+        .map(|&(size, _)| Vec::with_capacity(size))
+        .collect();
+    for (u, &first) in min_labels.iter().enumerate() {
+        if slot[first] != usize::MAX {
+            members[slot[first]].push(u);
+        }
+    }
+
+    let largest = sizes.first().map_or(0, |&(size, _)| size);
+    ComponentSummary {
+        count: sizes.len(),
+        largest,
+        largest_fraction: if labels.is_empty() {
+            0.0
+        } else {
+            largest as f64 / labels.len() as f64
+        },
+        distribution,
+        top: members,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -161,16 +595,13 @@ mod tests {
 
     #[test]
     fn test_wcc_simple() {
-        let graph = Graph {
-            num_nodes: 5,
-            edges: vec![
-                vec![1], // 0→1
-                vec![2], // 1→2
-                vec![],  // 2
-                vec![4], // 3→4
-                vec![],  // 4
-            ],
-        };
+        let graph = Graph::from_adjacency(vec![
+            vec![1], // 0→1
+            vec![2], // 1→2
+            vec![],  // 2
+            vec![4], // 3→4
+            vec![],  // 4
+        ]);
 
         let result = wcc_sequential(&graph);
 
@@ -184,10 +615,7 @@ mod tests {
 
     #[test]
     fn test_wcc_all_connected() {
-        let graph = Graph {
-            num_nodes: 4,
-            edges: vec![vec![1], vec![2], vec![3], vec![]],
-        };
+        let graph = Graph::from_adjacency(vec![vec![1], vec![2], vec![3], vec![]]);
 
         let result = wcc_sequential(&graph);
 
@@ -198,10 +626,7 @@ mod tests {
 
     #[test]
     fn test_wcc_all_disconnected() {
-        let graph = Graph {
-            num_nodes: 4,
-            edges: vec![vec![], vec![], vec![], vec![]],
-        };
+        let graph = Graph::from_adjacency(vec![vec![], vec![], vec![], vec![]]);
 
         let result = wcc_sequential(&graph);
 
@@ -227,10 +652,7 @@ mod tests {
     #[test]
     fn test_wcc_parallel_vs_sequential() {
         //0→1→2, 3→4
-        let graph = Graph {
-            num_nodes: 5,
-            edges: vec![vec![1], vec![2], vec![], vec![4], vec![]],
-        };
+        let graph = Graph::from_adjacency(vec![vec![1], vec![2], vec![], vec![4], vec![]]);
 
         let seq_result = wcc_sequential(&graph);
         let par_result = wcc_parallel(&graph, 4);
@@ -249,15 +671,40 @@ mod tests {
             par_result[3] == par_result[4]
         );
 
-        assert_ne!(par_result[0] == par_result[3], true);
+        assert!(par_result[0] != par_result[3]);
+    }
+
+    #[test]
+    fn test_canonical_labels() {
+        // 0-4, 1-2-3, 5
+        let graph = Graph::from_adjacency(vec![vec![4], vec![2], vec![3], vec![], vec![], vec![]]);
+
+        for labels in [wcc_sequential(&graph), wcc_parallel(&graph, 4)] {
+            assert_eq!(
+                canonical_labels(&labels, Labeling::Min),
+                vec![0, 1, 1, 1, 0, 5]
+            );
+            assert_eq!(
+                canonical_labels(&labels, Labeling::Dense),
+                vec![1, 0, 0, 0, 1, 2]
+            );
+        }
+
+        let summary = component_summary(&wcc_sequential(&graph), 2);
+        assert_eq!(summary.count, 3);
+        assert_eq!(summary.largest, 3);
+        assert!((summary.largest_fraction - 0.5).abs() < 1e-12);
+        assert_eq!(summary.distribution, vec![(3, 1), (2, 1), (1, 1)]);
+        assert_eq!(summary.top, vec![vec![1, 2, 3], vec![0, 4]]);
     }
 
     #[test]
     fn test_wcc_parallel_vs_sequential_large() {
+        use crate::formats::GraphFormat;
         use crate::graph_generator::generate_random;
 
         let path = "test_wcc_parallel.txt";
-        generate_random(1000, 5000, path).unwrap();
+        generate_random(1000, 5000, path, GraphFormat::EdgeList, false).unwrap();
 
         let graph = Graph::from_file(path).unwrap();
 
@@ -275,10 +722,11 @@ mod tests {
 
     #[test]
     fn test_wcc_parallel_vs_sequential_disconnected() {
+        use crate::formats::GraphFormat;
         use crate::graph_generator::generate_disconnected;
 
         let path = "test_wcc_parallel.txt";
-        generate_disconnected(1000, 5000, 11, path).unwrap();
+        generate_disconnected(1000, 5000, 11, path, GraphFormat::EdgeList, false).unwrap();
 
         let graph = Graph::from_file(path).unwrap();
 
@@ -290,7 +738,146 @@ mod tests {
         let par_components: HashSet<_> = par.iter().collect();
 
         assert_eq!(seq_components.len(), par_components.len());
+        assert_eq!(
+            canonical_labels(&seq, Labeling::Dense),
+            canonical_labels(&par, Labeling::Dense)
+        );
 
         std::fs::remove_file(path).ok();
     }
+
+    #[test]
+    fn test_wcc_algorithms() {
+        // sparse random edges plus a long directed path, so some nodes are only reached
+        // by edges that leave the giant component
+        let mut edges = Vec::new();
+        let mut x: usize = 11;
+        for _ in 0..1500 {
+            x = x
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let u = (x >> 33) % 2000;
+            x = x
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let v = (x >> 33) % 2000;
+            edges.push((u, v));
+        }
+        edges.extend((2000..2999).map(|i| (i, i + 1)));
+        edges.extend((0..300).map(|i| (i, 2000 + i * 3)));
+        let mut graph = Graph::from_edges(3100, &edges);
+        graph.build_reverse();
+
+        let expected = canonical_labels(&wcc_sequential(&graph), Labeling::Dense);
+        for labels in [
+            wcc_afforest(&graph, 4),
+            wcc_shiloach_vishkin(&graph, 4),
+            wcc_label_propagation(&graph, 4),
+        ] {
+            assert_eq!(canonical_labels(&labels, Labeling::Dense), expected);
+        }
+        // label propagation already ends with the smallest node of every component
+        assert_eq!(
+            wcc_label_propagation(&graph, 4),
+            canonical_labels(&expected, Labeling::Min)
+        );
+
+        // path 1..=500 is the giant with root 1, the third out-edges of 5 and 9 are skipped,
+        // so 0 (hangs the giant's root under itself) and 600-601 are found from the other end
+        let mut edges: Vec<(usize, usize)> = (1..500).map(|i| (i, i + 1)).collect();
+        edges.extend([(5, 7), (5, 0), (9, 11), (9, 600), (600, 601)]);
+        let mut graph = Graph::from_edges(700, &edges);
+        graph.build_reverse();
+        assert_eq!(
+            canonical_labels(&wcc_afforest(&graph, 4), Labeling::Dense),
+            canonical_labels(&wcc_sequential(&graph), Labeling::Dense)
+        );
+    }
+
+    #[test]
+    fn test_connectivity_batches() {
+        // 0-1 and 2-3 from the graph, 4 alone
+        let graph = Graph::from_edges(5, &[(0, 1), (3, 2)]);
+        let mut connectivity = Connectivity::from_graph(&graph, 4);
+        assert_eq!(connectivity.num_components(), 3);
+        assert!(connectivity.connected(1, 0));
+        assert!(!connectivity.connected(1, 2));
+        assert_eq!(connectivity.component_size(3), 2);
+
+        // one batch joins both pairs twice over, 6 is new, 5 only appears through it
+        connectivity.add_edges(&[(1, 2), (0, 3), (6, 4)]);
+        assert_eq!(connectivity.num_nodes(), 7);
+        assert_eq!(connectivity.num_components(), 3);
+        assert!(connectivity.connected(0, 2));
+        assert_eq!(connectivity.component_size(2), 4);
+        assert_eq!(connectivity.component_size(4), 2);
+        assert_eq!(connectivity.component_size(5), 1);
+
+        connectivity.add_edges(&[(5, 4), (4, 0)]);
+        assert_eq!(connectivity.num_components(), 1);
+        assert_eq!(connectivity.component_size(6), 7);
+    }
+
+    #[test]
+    fn test_connectivity_vs_wcc() {
+        use crate::formats::GraphFormat;
+        use crate::graph_generator::generate_random;
+
+        let path = "test_wcc_connectivity.txt";
+        generate_random(2000, 1500, path, GraphFormat::EdgeList, false).unwrap();
+        let graph = Graph::from_file(path).unwrap();
+        std::fs::remove_file(path).ok();
+
+        // the same edges in batches of 100
+        let mut edges = Vec::new();
+        for u in 0..graph.num_nodes {
+            edges.extend(graph.neighbors(u).iter().map(|&v| (u, v)));
+        }
+        let mut connectivity = Connectivity::new(graph.num_nodes, 4);
+        for batch in edges.chunks(100) {
+            connectivity.add_edges(batch);
+        }
+
+        let summary = component_summary(&wcc_sequential(&graph), 0);
+        assert_eq!(connectivity.num_components(), summary.count);
+        let labels = wcc_sequential(&graph);
+        let sizes = component_sizes(&canonical_labels(&labels, Labeling::Min));
+        let largest = sizes[0].1;
+        assert_eq!(connectivity.component_size(largest), summary.largest);
+        for u in 0..graph.num_nodes {
+            assert_eq!(
+                connectivity.connected(u, largest),
+                labels[u] == labels[largest]
+            );
+        }
+    }
+
+    #[test]
+    fn test_removal_curve() {
+        // path 0-1-2-3-4 and the pair 5-6
+        let mut graph = Graph::from_edges(7, &[(0, 1), (2, 1), (2, 3), (3, 4), (5, 6)]);
+        graph.build_reverse();
+
+        let order = [2, 5, 0, 1, 3, 4, 6];
+        let curve = removal_curve(&graph, &order, &[0, 1, 2, 3, 7]);
+        assert_eq!(curve, vec![(5, 2), (2, 3), (2, 3), (2, 3), (0, 0)]);
+
+        // the same numbers as WCC on what is left
+        for k in 0..=order.len() {
+            let keep: Vec<usize> = order[k..].to_vec();
+            let edges: Vec<(usize, usize)> = (0..7)
+                .flat_map(|u| graph.neighbors(u).iter().map(move |&v| (u, v)))
+                .filter(|&(u, v)| keep.contains(&u) && keep.contains(&v))
+                .collect();
+            let summary = component_summary(&wcc_sequential(&Graph::from_edges(7, &edges)), 0);
+            assert_eq!(
+                removal_curve(&graph, &order, &[k])[0],
+                (
+                    if keep.is_empty() { 0 } else { summary.largest },
+                    // the removed nodes are left over as single nodes
+                    summary.count - k
+                )
+            );
+        }
+    }
 }